@@ -0,0 +1,153 @@
+//! Prices shown in another currency. Calculations stay in CZK, only their display is converted.
+
+use crate::FormEvent;
+use dioxus::prelude::*;
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, EnumIter, Display, EnumString)]
+pub enum Currency {
+    #[default]
+    Czk,
+    Eur,
+}
+
+impl Currency {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::Czk => "Kč",
+            Currency::Eur => "€",
+        }
+    }
+}
+
+/// Currency prices are displayed in, chosen in the header.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayCurrency {
+    pub currency: Currency,
+    /// Exchange rate, used only for [`Currency::Eur`].
+    pub czk_per_eur: f64,
+}
+
+impl Default for DisplayCurrency {
+    fn default() -> Self {
+        Self { currency: Currency::default(), czk_per_eur: 25.0 }
+    }
+}
+
+impl DisplayCurrency {
+    /// `czk` rounded for a compact display: whole crowns, or euros with cents like
+    /// [`format_price()`](crate::format_price) formats them.
+    pub fn short(&self, czk: f64) -> String {
+        match self.currency {
+            Currency::Czk => format_czk(czk),
+            Currency::Eur => crate::format_price(czk, *self),
+        }
+    }
+}
+
+/// `czk` in whole crowns, like operators bill them, with (non-breaking) spaces between thousands.
+/// Halves are rounded up (away from zero), unlike `{:.0}` which rounds them to even. Only for
+/// display, calculations keep the unrounded value.
+pub fn format_czk(czk: f64) -> String {
+    let whole = czk.round();
+    let sign = if whole < 0.0 { "-" } else { "" };
+    let digits = format!("{:.0}", whole.abs());
+    format!("{sign}{}\u{a0}{}", group_thousands(&digits), Currency::Czk.symbol())
+}
+
+/// `digits` of a whole number with non-breaking spaces between thousands.
+pub fn group_thousands(digits: &str) -> String {
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push('\u{a0}');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// `czk` in the currency of `rate`, with the symbol to display it with.
+pub fn convert(czk: f64, rate: DisplayCurrency) -> (f64, &'static str) {
+    let value = match rate.currency {
+        Currency::Czk => czk,
+        Currency::Eur => czk / rate.czk_per_eur,
+    };
+    (value, rate.currency.symbol())
+}
+
+/// The currency chosen by [`CurrencySelect`], subscribing the calling component to its changes.
+pub fn use_currency() -> DisplayCurrency {
+    *use_context::<Signal<DisplayCurrency>>().read()
+}
+
+#[component]
+pub fn CurrencySelect() -> Element {
+    let mut display = use_context::<Signal<DisplayCurrency>>();
+
+    let currency_changed = move |evt: FormEvent| {
+        display.write().currency = evt.parsed()?;
+        Ok(())
+    };
+    // A zero or negative rate would make all prices nonsense, keep the previous one then.
+    let rate_changed = move |evt: FormEvent| {
+        let czk_per_eur: f64 = evt.parsed()?;
+        if czk_per_eur > 0.0 {
+            display.write().czk_per_eur = czk_per_eur;
+        }
+        Ok(())
+    };
+
+    rsx! {
+        select { id: "currency-select",
+            onchange: currency_changed,
+            for currency in Currency::iter() {
+                option { value: "{currency}",
+                    selected: display.read().currency == currency,
+                    "{currency.symbol()}"
+                }
+            }
+        }
+        if display.read().currency == Currency::Eur {
+            " "
+            input { id: "currency-rate",
+                r#type: "number",
+                title: "Kč/€",
+                value: display.read().czk_per_eur,
+                onchange: rate_changed,
+                min: 0,
+                step: 0.01,
+            }
+            label { for: "currency-rate", " Kč/€" }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn test_convert() {
+        let eur = DisplayCurrency { currency: Currency::Eur, czk_per_eur: 25.0 };
+        assert_eq!(convert(250.0, eur), (10.0, "€"));
+        assert_eq!(convert(277.47, eur).0, 277.47 / 25.0);
+        assert_eq!(convert(250.0, DisplayCurrency::default()), (250.0, "Kč"));
+
+        assert_eq!(eur.short(249.0), "9,96\u{a0}€");
+        assert_eq!(eur.short(-50.0), "-2,00\u{a0}€");
+        assert_eq!(DisplayCurrency::default().short(249.4), "249\u{a0}Kč");
+    }
+
+    #[test]
+    fn test_format_czk() {
+        assert_eq!(format_czk(1234.5), "1\u{a0}235\u{a0}Kč");
+        assert_eq!(format_czk(999.49), "999\u{a0}Kč");
+        assert_eq!(format_czk(0.0), "0\u{a0}Kč");
+        assert_eq!(format_czk(2.5), "3\u{a0}Kč", "not rounded to even");
+        assert_eq!(format_czk(-0.4), "0\u{a0}Kč");
+        assert_eq!(format_czk(-1099.5), "-1\u{a0}100\u{a0}Kč");
+        assert_eq!(format_czk(1_234_567.0), "1\u{a0}234\u{a0}567\u{a0}Kč");
+    }
+}
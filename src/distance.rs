@@ -0,0 +1,265 @@
+//! Trip distance computed from its endpoints by a routing service instead of typed manually.
+
+use crate::TripInputData;
+use anyhow::{Result, anyhow, bail};
+use dioxus::prelude::*;
+use serde_json::json;
+use tracing::error;
+
+/// Routing service, a trait so that it can be replaced by a test double.
+pub trait DistanceProvider {
+    /// Driving distance between `from` and `to` in kilometers.
+    fn distance_km(&self, from: &Place, to: &Place) -> impl Future<Output = Result<f64>>;
+}
+
+/// One end of the trip as entered by the user.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Place {
+    /// Latitude and longitude in degrees.
+    Coordinates(f64, f64),
+    /// Anything else, looked up by the routing service.
+    Address(String),
+}
+
+impl Place {
+    /// Coordinates if `text` is `latitude, longitude` (e.g. copied from a map), an address otherwise.
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        if text.is_empty() {
+            bail!("empty place");
+        }
+        let coordinates = text.split_once(',').and_then(|(lat, lon)| {
+            let (lat, lon): (f64, f64) = (lat.trim().parse().ok()?, lon.trim().parse().ok()?);
+            ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)).then_some((lat, lon))
+        });
+        Ok(match coordinates {
+            Some((lat, lon)) => Self::Coordinates(lat, lon),
+            None => Self::Address(text.to_string()),
+        })
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Self::Coordinates(lat, lon) => json!({ "lat": lat, "lon": lon }),
+            Self::Address(address) => json!({ "q": address }),
+        }
+    }
+}
+
+/// Addresses geocoded by OpenStreetMap Nominatim, routes by the public OSRM demo server. Both are
+/// queried by the browser.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OsrmDistance;
+
+impl DistanceProvider for OsrmDistance {
+    fn distance_km(&self, from: &Place, to: &Place) -> impl Future<Output = Result<f64>> {
+        let js = format!(
+            r#"
+            async function coordinates(place) {{
+                if (place.q === undefined) return place;
+                const url = "https://nominatim.openstreetmap.org/search?format=json&limit=1&q="
+                    + encodeURIComponent(place.q);
+                const found = await (await fetch(url)).json();
+                if (found.length === 0) throw new Error("unknown place " + place.q);
+                return {{ lat: found[0].lat, lon: found[0].lon }};
+            }}
+            const [from, to] = [await coordinates({}), await coordinates({})];
+            const url = "https://router.project-osrm.org/route/v1/driving/"
+                + `${{from.lon}},${{from.lat}};${{to.lon}},${{to.lat}}?overview=false`;
+            const route = await (await fetch(url)).json();
+            if (route.code !== "Ok") throw new Error("no route: " + route.code);
+            return route.routes[0].distance;
+            "#,
+            from.to_json(),
+            to.to_json()
+        );
+        async move {
+            let meters: f64 =
+                document::eval(&js).join().await.map_err(|e| anyhow!("routing failed: {e}"))?;
+            Ok(meters / 1000.0)
+        }
+    }
+}
+
+/// Kilometers between `from` and `to` as typed, rounded to 100 m like a manual input would be.
+pub async fn compute_km(provider: &impl DistanceProvider, from: &str, to: &str) -> Result<f64> {
+    let (from, to) = (Place::parse(from)?, Place::parse(to)?);
+    let km = provider.distance_km(&from, &to).await?;
+    if !km.is_finite() || km < 0.0 {
+        bail!("invalid distance {km} km");
+    }
+    Ok((km * 10.0).round() / 10.0)
+}
+
+/// State of the lookup of [`DistancePicker`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DistanceStatus {
+    /// Nothing requested yet.
+    Idle,
+    Computing,
+    Done(f64),
+    Failed(String),
+}
+
+impl DistanceStatus {
+    /// Status from the value of the lookup resource, which is `None` while its future runs and
+    /// `Some(None)` when no lookup was `requested`.
+    pub fn new(requested: bool, value: Option<&Option<Result<f64, String>>>) -> Self {
+        match (requested, value) {
+            (false, _) | (true, Some(None)) => Self::Idle,
+            (true, None) => Self::Computing,
+            (true, Some(Some(Ok(km)))) => Self::Done(*km),
+            (true, Some(Some(Err(e)))) => Self::Failed(e.clone()),
+        }
+    }
+}
+
+/// Fill in [`TripInputData::km`] from the trip endpoints, it stays editable afterwards. `computing`
+/// is set while the lookup runs, so that results for the old distance are not shown meanwhile.
+#[component]
+pub fn DistancePicker(input_data: Signal<TripInputData>, computing: Signal<bool>) -> Element {
+    let mut from = use_signal(String::new);
+    let mut to = use_signal(String::new);
+    // Endpoints of the last requested lookup, the resource reruns when they are set.
+    let mut requested = use_signal(|| None::<(String, String)>);
+
+    let mut lookup = use_resource(move || async move {
+        let (from, to) = requested()?;
+        let result = compute_km(&OsrmDistance, &from, &to).await;
+        match &result {
+            Ok(km) => input_data.write().km = *km,
+            Err(e) => error!("computing distance: {e:#}"),
+        }
+        Some(result.map_err(|e| format!("{e:#}")))
+    });
+    let status = DistanceStatus::new(requested.read().is_some(), lookup.read().as_ref());
+    use_effect(move || {
+        let status = DistanceStatus::new(requested.read().is_some(), lookup.read().as_ref());
+        computing.set(status == DistanceStatus::Computing);
+    });
+
+    let compute = move |_| requested.set(Some((from(), to())));
+    let busy = status == DistanceStatus::Computing;
+
+    rsx! {
+        p {
+            label { for: "input-from", "Odkud " },
+            input { id: "input-from",
+                placeholder: "adresa nebo 50.087, 14.421",
+                value: from,
+                onchange: move |evt: FormEvent| from.set(evt.value()),
+            },
+            label { for: "input-to", " kam " },
+            input { id: "input-to",
+                value: to,
+                onchange: move |evt: FormEvent| to.set(evt.value()),
+            },
+            " ",
+            button { onclick: compute,
+                disabled: busy,
+                "spočítat km"
+            },
+            match status {
+                DistanceStatus::Computing => rsx! {
+                    span { class: "note", " ", span { class: "spinner" }, " počítám..." }
+                },
+                DistanceStatus::Failed(e) => rsx! {
+                    span { class: "unavailable", " nepodařilo se: {e} " }
+                    button { onclick: move |_| lookup.restart(), "zkusit znovu" }
+                },
+                DistanceStatus::Idle | DistanceStatus::Done(_) => rsx! {},
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        future::poll_fn,
+        mem,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+    use test_log::test;
+
+    /// Returns a fixed distance, checking that the places were parsed as expected.
+    struct FixedDistance(f64);
+
+    impl DistanceProvider for FixedDistance {
+        fn distance_km(&self, from: &Place, to: &Place) -> impl Future<Output = Result<f64>> {
+            assert_eq!(from, &Place::Address("Václavské náměstí".into()));
+            assert_eq!(to, &Place::Coordinates(50.1, 14.26));
+            std::future::ready(Ok(self.0))
+        }
+    }
+
+    /// Like a network request, not ready when first polled.
+    struct SlowDistance;
+
+    impl DistanceProvider for SlowDistance {
+        fn distance_km(&self, _from: &Place, _to: &Place) -> impl Future<Output = Result<f64>> {
+            let mut polled = false;
+            poll_fn(move |_| {
+                if mem::replace(&mut polled, true) { Poll::Ready(Ok(2.04)) } else { Poll::Pending }
+            })
+        }
+    }
+
+    /// The test doubles are ready immediately, no executor needed.
+    fn now<T>(future: impl Future<Output = T>) -> T {
+        match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("future not ready"),
+        }
+    }
+
+    #[test]
+    fn test_parse_place() {
+        assert_eq!(Place::parse(" 50.087, 14.421 ").unwrap(), Place::Coordinates(50.087, 14.421));
+        assert_eq!(Place::parse("Letiště Praha").unwrap(), Place::Address("Letiště Praha".into()));
+        assert_eq!(
+            Place::parse("Na Příkopě 1, Praha").unwrap(),
+            Place::Address("Na Příkopě 1, Praha".into())
+        );
+        assert_eq!(Place::parse("91, 14").unwrap(), Place::Address("91, 14".into()));
+        assert_eq!(Place::parse("  ").unwrap_err().to_string(), "empty place");
+    }
+
+    #[test]
+    fn test_compute_km() {
+        let from = "Václavské náměstí";
+        let to = "50.1,14.26";
+        assert_eq!(now(compute_km(&FixedDistance(17.349), from, to)).unwrap(), 17.3);
+
+        let error = now(compute_km(&FixedDistance(f64::NAN), from, to)).unwrap_err();
+        assert_eq!(error.to_string(), "invalid distance NaN km");
+        let error = now(compute_km(&FixedDistance(1.0), from, "")).unwrap_err();
+        assert_eq!(error.to_string(), "empty place");
+    }
+
+    #[test]
+    fn test_distance_status() {
+        assert_eq!(DistanceStatus::new(false, None), DistanceStatus::Idle);
+
+        // The resource has no value while its future runs.
+        let mut lookup = pin!(compute_km(&SlowDistance, "Praha", "Kladno"));
+        let mut context = Context::from_waker(Waker::noop());
+        assert!(lookup.as_mut().poll(&mut context).is_pending());
+        assert_eq!(DistanceStatus::new(true, None), DistanceStatus::Computing);
+
+        let Poll::Ready(result) = lookup.as_mut().poll(&mut context) else {
+            panic!("lookup not finished");
+        };
+        let value = Some(result.map_err(|e| format!("{e:#}")));
+        assert_eq!(DistanceStatus::new(true, Some(&value)), DistanceStatus::Done(2.0));
+
+        let failed = Some(Err("routing failed: offline".to_string()));
+        assert_eq!(
+            DistanceStatus::new(true, Some(&failed)),
+            DistanceStatus::Failed("routing failed: offline".into())
+        );
+        assert_eq!(DistanceStatus::new(true, Some(&None)), DistanceStatus::Idle);
+    }
+}
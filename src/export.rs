@@ -0,0 +1,119 @@
+//! Export of the comparison for spreadsheets.
+
+use crate::provider::CalculationResult;
+use anyhow::Result;
+use csv::WriterBuilder;
+use dioxus::prelude::*;
+use tracing::error;
+
+/// CSV of `results` from [`compare_all()`](crate::provider::compare_all), a row for each price
+/// component and a total for each provider. Semicolon separated with decimal commas, like
+/// spreadsheets in the Czech locale expect.
+pub fn comparison_csv(results: &[CalculationResult]) -> Result<String> {
+    fn czk(czk: f64) -> String {
+        format!("{czk:.2}").replace('.', ",")
+    }
+
+    let mut writer = WriterBuilder::new().delimiter(b';').from_writer(vec![]);
+    writer.write_record(["poskytovatel", "kategorie", "položka", "Kč"])?;
+    for result in results {
+        let name = result.provider_name();
+        for component in &result.components {
+            writer.write_record([name, &result.car_type, &component.name, &czk(component.czk)])?;
+        }
+        writer.write_record([name, &result.car_type, "celkem", &czk(result.total_czk())])?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// Let the browser save `contents` as a file.
+pub fn download(file_name: &str, mime_type: &str, contents: &str) {
+    let js = format!(
+        r#"
+        const blob = new Blob([{}], {{ type: {} }});
+        const link = document.createElement("a");
+        link.href = URL.createObjectURL(blob);
+        link.download = {};
+        link.click();
+        URL.revokeObjectURL(link.href);
+        "#,
+        serde_json::Value::from(contents),
+        serde_json::Value::from(mime_type),
+        serde_json::Value::from(file_name)
+    );
+    document::eval(&js);
+}
+
+#[component]
+pub fn CsvExport(results: Memo<Vec<CalculationResult>>) -> Element {
+    let download = move |_| {
+        let csv = match comparison_csv(&results.read()) {
+            Ok(csv) => csv,
+            Err(e) => return error!("exporting CSV: {e:#}"),
+        };
+        // With the byte order mark spreadsheets recognize UTF-8.
+        download("za-kolik-pojedu.csv", "text/csv;charset=utf-8", &format!("\u{feff}{csv}"));
+    };
+
+    rsx! {
+        p {
+            button { onclick: download, disabled: results.read().is_empty(), "Stáhnout CSV" }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        TripInputData,
+        provider::{Provider, tests::with_signals, walk::Walk},
+    };
+    use jiff::{ToSpan, civil::date};
+    use test_log::test;
+
+    #[test]
+    fn test_comparison_csv() {
+        let mut car4way = CalculationResult::new("Legend (Fabia)");
+        car4way.provider = Some("car4way");
+        car4way.add_component(249.0, "2 hodiny + 10 km");
+        car4way.add_component(28.47, "extra za 3 km");
+        let mut walk = CalculationResult::new("pěšky/na kole");
+        walk.provider = Some("pěšky");
+        walk.add_component(0.0, "pěšky; nebo na kole");
+
+        let csv = comparison_csv(&[car4way, walk]).unwrap();
+        assert!(csv.ends_with('\n'));
+        assert_eq!(
+            csv.lines().collect::<Vec<_>>(),
+            [
+                "poskytovatel;kategorie;položka;Kč",
+                "car4way;Legend (Fabia);2 hodiny + 10 km;249,00",
+                "car4way;Legend (Fabia);extra za 3 km;28,47",
+                "car4way;Legend (Fabia);celkem;277,47",
+                "pěšky;pěšky/na kole;\"pěšky; nebo na kole\";0,00",
+                "pěšky;pěšky/na kole;celkem;0,00",
+            ]
+        );
+        assert_eq!(comparison_csv(&[]).unwrap(), "poskytovatel;kategorie;položka;Kč\n");
+    }
+
+    #[test]
+    fn test_comparison_csv_segments() {
+        with_signals(|| {
+            let walk = Provider::new(Signal::new(true), Signal::new(Walk::default()));
+            let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+            let segments = [
+                TripInputData::from_times(1.0, begin, begin + 20.minutes()),
+                TripInputData::from_times(2.0, begin + 3.hours(), begin + 4.hours()),
+            ];
+            let result = walk.calculate_segments(&segments).unwrap();
+
+            let csv = comparison_csv(&[result]).unwrap();
+            assert_eq!(
+                csv.lines().skip(1).map(|line| line.split(';').nth(2).unwrap()).collect::<Vec<_>>(),
+                ["1. úsek: pěšky/na kole (0 Kč)", "2. úsek: pěšky/na kole (0 Kč)", "celkem"]
+            );
+        })
+    }
+}
@@ -0,0 +1,192 @@
+//! User reports whether computed prices match what the providers actually billed.
+
+use crate::{
+    FormEvent, TripInputData,
+    currency::use_currency,
+    provider::{CalculationResult, PriceComponent},
+};
+use dioxus::prelude::*;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    Matches,
+    Differs { actual_czk: f64 },
+}
+
+/// A single report of the user for a provider and a concrete trip.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feedback {
+    pub provider: String,
+    pub input_data: TripInputData,
+    pub result: CalculationResult,
+    pub verdict: Verdict,
+}
+
+/// What maintainers need to investigate a reported price mismatch.
+#[derive(Debug, Serialize)]
+struct Discrepancy<'a> {
+    provider: &'a str,
+    trip: &'a TripInputData,
+    car_type: &'a str,
+    computed_czk: f64,
+    actual_czk: f64,
+    breakdown: &'a [PriceComponent],
+}
+
+/// Pretty-printed JSON array of the feedback entries that don't match reality.
+pub fn export_discrepancies(feedback: &[Feedback]) -> serde_json::Result<String> {
+    let discrepancies: Vec<_> = feedback
+        .iter()
+        .filter_map(|entry| match entry.verdict {
+            Verdict::Matches => None,
+            Verdict::Differs { actual_czk } => Some(Discrepancy {
+                provider: &entry.provider,
+                trip: &entry.input_data,
+                car_type: &entry.result.car_type,
+                computed_czk: entry.result.total_czk(),
+                actual_czk,
+                breakdown: &entry.result.components,
+            }),
+        })
+        .collect();
+    serde_json::to_string_pretty(&discrepancies)
+}
+
+#[component]
+pub fn FeedbackControls(
+    provider: String,
+    input_data: TripInputData,
+    result: CalculationResult,
+    feedback: Signal<Vec<Feedback>>,
+) -> Element {
+    let currency = use_currency();
+    let mut actual_czk = use_signal(|| None::<f64>);
+
+    let last_verdict =
+        feedback.read().iter().rev().find(|entry| entry.provider == provider).map(|e| e.verdict);
+    let report = {
+        let provider = provider.clone();
+        use_callback(move |verdict| {
+            let entry = Feedback {
+                provider: provider.clone(),
+                input_data,
+                result: result.clone(),
+                verdict,
+            };
+            feedback.write().push(entry);
+        })
+    };
+    let actual_changed = move |evt: FormEvent| {
+        let value = evt.value();
+        actual_czk.set(if value.is_empty() { None } else { Some(value.parse()?) });
+        Ok(())
+    };
+
+    rsx! {
+        p { class: "feedback",
+            button { onclick: move |_| report(Verdict::Matches), "sedí" }
+            " "
+            input { id: "provider-{provider}-actual-czk",
+                r#type: "number",
+                placeholder: "skutečná cena (Kč)",
+                value: actual_czk().map(|czk| czk.to_string()),
+                onchange: actual_changed,
+                min: 0,
+            }
+            button {
+                disabled: actual_czk().is_none(),
+                onclick: move |_| {
+                    if let Some(actual_czk) = actual_czk() {
+                        report(Verdict::Differs { actual_czk });
+                    }
+                },
+                "nesedí"
+            }
+            match last_verdict {
+                None => rsx! {},
+                Some(Verdict::Matches) => rsx! { span { class: "note", " naposledy ověřeno: sedí" } },
+                Some(Verdict::Differs { actual_czk }) => rsx! {
+                    span { class: "note", " naposledy ověřeno: nesedí, účtováno {currency.short(actual_czk)}" }
+                },
+            }
+        }
+    }
+}
+
+#[component]
+pub fn FeedbackExport(feedback: Signal<Vec<Feedback>>) -> Element {
+    let export = match export_discrepancies(&feedback.read()) {
+        Ok(json) => json,
+        Err(e) => format!("chyba exportu: {e}"),
+    };
+
+    rsx! {
+        details { id: "feedback", class: "top-section",
+            summary { "Nahlášené nesrovnalosti ({feedback.read().len()} hodnocení)" },
+            p { "Zkopírujte a pošlete správcům:" }
+            textarea { id: "feedback-export", readonly: true, rows: 10, cols: 80, value: export }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::{ToSpan, civil::date};
+    use serde_json::json;
+    use test_log::test;
+
+    #[test]
+    fn test_export_discrepancies() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(5.0, begin, begin + 1.hour());
+        let mut result = CalculationResult::new("Legend (Fabia)");
+        result.add_component(249.0, "2 hodiny + 10 km");
+
+        let feedback = [
+            Feedback {
+                provider: "car4way".into(),
+                input_data,
+                result: result.clone(),
+                verdict: Verdict::Matches,
+            },
+            Feedback {
+                provider: "car4way".into(),
+                input_data,
+                result,
+                verdict: Verdict::Differs { actual_czk: 279.0 },
+            },
+        ];
+
+        let exported: serde_json::Value =
+            serde_json::from_str(&export_discrepancies(&feedback).unwrap()).unwrap();
+        assert_eq!(
+            exported,
+            json!([{
+                "provider": "car4way",
+                "trip": {
+                    "km": 5.0,
+                    "begin": "2025-06-03T10:00:00",
+                    "end": "2025-06-03T11:00:00",
+                    "reservation_begin": null,
+                    "rounding": "Pessimistic",
+                    "return_location": "InZone",
+                    "fuel_price_czk_per_l": 38.0,
+                    "idle_minutes": 0,
+                    "driving_minutes": null,
+                    "budget_czk": null,
+                    "airport_enter": false,
+                    "airport_leave": false,
+                    "passengers": 1,
+                    "round_trip": false,
+                    "discount": { "kind": "None", "amount": 0.0 },
+                },
+                "car_type": "Legend (Fabia)",
+                "computed_czk": 249.0,
+                "actual_czk": 279.0,
+                "breakdown": [{ "czk": 249.0, "name": "2 hodiny + 10 km" }],
+            }])
+        );
+    }
+}
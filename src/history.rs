@@ -0,0 +1,144 @@
+use crate::{FormEvent, TripInputData};
+use dioxus::prelude::*;
+use std::collections::BTreeSet;
+
+/// A trip saved by the user for later recall.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedTrip {
+    pub name: String,
+    pub input_data: TripInputData,
+    pub further_segments: Vec<TripInputData>,
+    pub tags: Vec<String>,
+}
+
+impl SavedTrip {
+    /// Create saved trip with tags parsed from comma-separated `tags`.
+    pub fn new(
+        name: impl Into<String>,
+        input_data: TripInputData,
+        further_segments: Vec<TripInputData>,
+        tags: &str,
+    ) -> Self {
+        let tags =
+            tags.split(',').map(str::trim).filter(|tag| !tag.is_empty()).map(Into::into).collect();
+        Self { name: name.into(), input_data, further_segments, tags }
+    }
+
+    fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+/// Saved trips having `tag`, or all of them if `tag` is `None`.
+pub fn filter_by_tag<'a>(
+    trips: &'a [SavedTrip],
+    tag: Option<&'a str>,
+) -> impl Iterator<Item = (usize, &'a SavedTrip)> {
+    trips.iter().enumerate().filter(move |(_, trip)| tag.is_none_or(|tag| trip.has_tag(tag)))
+}
+
+#[component]
+pub fn History(
+    input_data: Signal<TripInputData>,
+    further_segments: Signal<Vec<TripInputData>>,
+) -> Element {
+    let mut saved_trips = use_signal(Vec::<SavedTrip>::new);
+    let mut new_name = use_signal(String::new);
+    let mut new_tags = use_signal(String::new);
+    let mut tag_filter = use_signal(|| None::<String>);
+
+    let save = move |_| {
+        let name = match new_name.read().trim() {
+            "" => format!("Cesta {}", saved_trips.read().len() + 1),
+            name => name.to_string(),
+        };
+        let trip = SavedTrip::new(name, input_data(), further_segments(), &new_tags.read());
+        saved_trips.write().push(trip);
+    };
+    let tag_filter_changed = move |evt: FormEvent| {
+        let value = evt.value();
+        tag_filter.set((!value.is_empty()).then_some(value));
+    };
+
+    let all_tags: BTreeSet<String> =
+        saved_trips.read().iter().flat_map(|trip| trip.tags.iter().cloned()).collect();
+    let saved_trips_read = saved_trips.read();
+    let filtered: Vec<_> = filter_by_tag(&saved_trips_read, tag_filter.read().as_deref())
+        .map(|(i, trip)| (i, trip.name.clone(), trip.tags.join(", ")))
+        .collect();
+
+    rsx! {
+        div { id: "history", class: "top-section",
+            h2 { "Uložené cesty" },
+            p {
+                input { id: "history-name",
+                    placeholder: "název",
+                    value: "{new_name}",
+                    oninput: move |evt| new_name.set(evt.value()),
+                }
+                input { id: "history-tags",
+                    placeholder: "štítky (práce, osobní)",
+                    value: "{new_tags}",
+                    oninput: move |evt| new_tags.set(evt.value()),
+                }
+                button { onclick: save, "Uložit" }
+            }
+            p {
+                label { for: "history-filter", "Štítek " },
+                select { id: "history-filter",
+                    onchange: tag_filter_changed,
+                    option { value: "", "všechny" }
+                    for tag in all_tags {
+                        option { value: "{tag}",
+                            selected: tag_filter.read().as_deref() == Some(tag.as_str()),
+                            "{tag}"
+                        }
+                    }
+                }
+            }
+            ul {
+                for (i, name, tags) in filtered {
+                    li { key: "{i}",
+                        button {
+                            onclick: move |_| {
+                                let saved = saved_trips.read()[i].clone();
+                                input_data.set(saved.input_data);
+                                further_segments.set(saved.further_segments);
+                            },
+                            "{name}"
+                        }
+                        " {tags}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::{ToSpan, civil::date};
+    use test_log::test;
+
+    #[test]
+    fn test_filter_by_tag() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(10.0, begin, begin + 1.hour());
+        let trips = [
+            SavedTrip::new("do práce", input_data, vec![], "práce"),
+            SavedTrip::new("na chatu", input_data, vec![], " osobní , víkend,"),
+            SavedTrip::new("k doktorovi", input_data, vec![], ""),
+            SavedTrip::new("služebka", input_data, vec![input_data], "práce,víkend"),
+        ];
+        assert_eq!(trips[1].tags, ["osobní", "víkend"]);
+
+        let names = |tag| -> Vec<_> {
+            filter_by_tag(&trips, tag).map(|(_, trip)| trip.name.as_str()).collect()
+        };
+        assert_eq!(names(Some("práce")), ["do práce", "služebka"]);
+        assert_eq!(names(Some("víkend")), ["na chatu", "služebka"]);
+        assert_eq!(names(Some("neznámý")), Vec::<&str>::new());
+        assert_eq!(names(None).len(), 4);
+    }
+}
@@ -0,0 +1,259 @@
+//! Translations of the user interface texts, Czech being the original language.
+
+use crate::FormEvent;
+use dioxus::prelude::*;
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+
+/// Language of the user interface, chosen in the header.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, EnumIter, Display, EnumString)]
+pub enum Lang {
+    #[default]
+    Cs,
+    En,
+}
+
+impl Lang {
+    /// Name of the language in itself, for the selector.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Lang::Cs => "česky",
+            Lang::En => "English",
+        }
+    }
+}
+
+/// A translated text, see [`t()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum Key {
+    Providers,
+    ShowDisabled,
+    SortByPrice,
+    ShowWithoutPackages,
+    ShowDebug,
+    CompareWithoutAirport,
+    Trip,
+    Kilometers,
+    RoundTrip,
+    Passengers,
+    Begin,
+    End,
+    ReservationFrom,
+    QuickChoice,
+    TimePresets,
+    NowForAnHour,
+    WholeDay,
+    Weekend,
+    Overnight,
+    BeginWeekday,
+    IdleMinutes,
+    DrivingMinutes,
+    WholeTime,
+    Budget,
+    Unlimited,
+    Discount,
+    FuelPrice,
+    CarReturn,
+    FromAirport,
+    ToAirport,
+    Rounding,
+    TotalTime,
+    KmOnlyNote,
+    FurtherSegments,
+    Segment,
+    SegmentBegin,
+    SegmentEnd,
+    RemoveSegment,
+    AddSegment,
+    Cheapest,
+    Baseline,
+    MoveLeft,
+    MoveRight,
+    PerPerson,
+    PurelyPerMinute,
+    WithoutAirport,
+    Error,
+    CalculationError,
+    WaitingForDistance,
+    AddKm,
+    SubtractKm,
+    Total,
+    EndBeforeBegin,
+    SingleSegmentOnly,
+    Tariff,
+    ShowAllTariffs,
+    CarTypes,
+    InsuranceFor,
+    CzkPerDay,
+    OutOfZoneReturn,
+    IdleRate,
+    LikeDriving,
+    CzkPerMinute,
+    MonthlyUsage,
+    RecommendedTariff,
+    MonthlyTrips,
+    MonthlyKm,
+    TypicalTripMinutes,
+    BusinessCustomer,
+    CarType,
+    Package,
+    Price,
+    Chosen,
+    UnverifiedPrices,
+}
+
+/// Text of `key` in `lang`.
+pub fn t(key: Key, lang: Lang) -> &'static str {
+    let (cs, en) = match key {
+        Key::Providers => ("Poskytovatelé", "Providers"),
+        Key::ShowDisabled => (
+            "zobrazit cenu i u vypnutých (nezapočítává se)",
+            "show price of disabled ones too (not compared)",
+        ),
+        Key::SortByPrice => ("seřadit podle ceny", "sort by price"),
+        Key::ShowWithoutPackages => ("ukázat i čistě minutovou cenu", "show per-minute price too"),
+        Key::ShowDebug => ("zobrazit ladicí údaje", "show debug info"),
+        Key::CompareWithoutAirport => {
+            ("porovnat i bez letištních poplatků", "compare without airport fees too")
+        },
+        Key::Trip => ("Cesta", "Trip"),
+        Key::Kilometers => ("Počet km", "Kilometers"),
+        Key::RoundTrip => ("tam i zpět", "round trip"),
+        Key::Passengers => ("Počet osob", "Passengers"),
+        Key::Begin => ("Začátek", "Begin"),
+        Key::End => ("Konec", "End"),
+        Key::ReservationFrom => ("Rezervace od", "Reserved from"),
+        Key::QuickChoice => ("Rychlá volba", "Quick choice"),
+        Key::TimePresets => ("Kdy", "When"),
+        Key::NowForAnHour => ("teď na hodinu", "now for an hour"),
+        Key::WholeDay => ("celý den", "whole day"),
+        Key::Weekend => ("víkend", "weekend"),
+        Key::Overnight => ("přes noc", "overnight"),
+        Key::BeginWeekday => ("Den začátku", "Day of begin"),
+        Key::IdleMinutes => ("Z toho parkování (min)", "Of that parked (min)"),
+        Key::DrivingMinutes => ("Odhadovaná doba jízdy (min)", "Estimated driving time (min)"),
+        Key::WholeTime => ("celá doba", "whole time"),
+        Key::Budget => ("Rozpočet (Kč)", "Budget (CZK)"),
+        Key::Unlimited => ("neomezený", "unlimited"),
+        Key::Discount => ("Sleva", "Discount"),
+        Key::FuelPrice => ("Cena paliva (Kč/l)", "Fuel price (CZK/l)"),
+        Key::CarReturn => ("Vrácení auta", "Car return"),
+        Key::FromAirport => ("z letiště", "from the airport"),
+        Key::ToAirport => ("na letiště", "to the airport"),
+        Key::Rounding => ("Zaokrouhlování", "Rounding"),
+        Key::TotalTime => ("Celkový čas", "Total time"),
+        Key::KmOnlyNote => (
+            "Nulová doba pronájmu: účtují se jen kilometry, auto ale musíte mít půjčené.",
+            "Zero rental time: just kilometers are billed, but you need to have the car rented.",
+        ),
+        Key::FurtherSegments => ("Další úseky", "Further segments"),
+        Key::Segment => ("úsek", "segment"),
+        Key::SegmentBegin => ("začátek", "begin"),
+        Key::SegmentEnd => ("konec", "end"),
+        Key::RemoveSegment => ("odebrat", "remove"),
+        Key::AddSegment => ("přidat úsek", "add segment"),
+        Key::Cheapest => ("nejlevnější", "cheapest"),
+        Key::Baseline => ("základ srovnání", "baseline"),
+        Key::MoveLeft => ("posunout doleva", "move left"),
+        Key::MoveRight => ("posunout doprava", "move right"),
+        Key::PerPerson => ("cena na osobu", "price per person"),
+        Key::PurelyPerMinute => ("čistě minutově", "purely per minute"),
+        Key::WithoutAirport => ("bez letiště", "without airport"),
+        Key::Error => ("chyba", "error"),
+        Key::CalculationError => ("chyba výpočtu", "calculation error"),
+        Key::WaitingForDistance => ("čekám na vzdálenost...", "waiting for the distance..."),
+        Key::AddKm => ("přidat", "add"),
+        Key::SubtractKm => ("ubrat", "subtract"),
+        Key::Total => ("celkem", "total"),
+        Key::EndBeforeBegin => ("konec je před začátkem", "end is before begin"),
+        Key::SingleSegmentOnly => {
+            ("jen pro cestu bez dalších úseků", "only for a trip without further segments")
+        },
+        Key::Tariff => ("Tarif", "Tariff"),
+        Key::ShowAllTariffs => ("zobrazit všechny tarify", "show all tariffs"),
+        Key::CarTypes => ("Kategorie aut", "Car types"),
+        Key::InsuranceFor => ("pojištění za", "insurance for"),
+        Key::CzkPerDay => ("Kč/den", "CZK/day"),
+        Key::OutOfZoneReturn => ("Vrácení mimo zónu", "Return out of the zone"),
+        Key::IdleRate => ("Parkovné", "Parking rate"),
+        Key::LikeDriving => ("jako jízda", "like driving"),
+        Key::CzkPerMinute => ("Kč/min", "CZK/min"),
+        Key::MonthlyUsage => ("Měsíční využití", "Monthly usage"),
+        Key::RecommendedTariff => ("Doporučený tarif", "Recommended tariff"),
+        Key::MonthlyTrips => ("Počet jízd", "Number of trips"),
+        Key::MonthlyKm => ("Celkem km", "Total km"),
+        Key::TypicalTripMinutes => ("Typická délka jízdy (min)", "Typical trip length (min)"),
+        Key::BusinessCustomer => ("firemní zákazník", "business customer"),
+        Key::CarType => ("kategorie", "car type"),
+        Key::Package => ("balíček", "package"),
+        Key::Price => ("cena", "price"),
+        Key::Chosen => ("vybráno", "chosen"),
+        Key::UnverifiedPrices => (
+            "Ceny nejsou ověřené s aktuálním ceníkem, proto je poskytovatel ve výchozím stavu \
+             vypnutý.",
+            "The prices are not verified against the current price list, so the provider is \
+             disabled by default.",
+        ),
+    };
+    match lang {
+        Lang::Cs => cs,
+        Lang::En => en,
+    }
+}
+
+/// Names of weekdays from Monday.
+pub fn weekday_names(lang: Lang) -> [&'static str; 7] {
+    match lang {
+        Lang::Cs => ["pondělí", "úterý", "středa", "čtvrtek", "pátek", "sobota", "neděle"],
+        Lang::En => ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"],
+    }
+}
+
+/// How much `amount` is over the budget.
+pub fn over_budget(amount: &str, lang: Lang) -> String {
+    match lang {
+        Lang::Cs => format!("o {amount} nad rozpočet"),
+        Lang::En => format!("{amount} over budget"),
+    }
+}
+
+/// The language chosen by [`LangSelect`], subscribing the calling component to its changes.
+pub fn use_lang() -> Lang {
+    *use_context::<Signal<Lang>>().read()
+}
+
+#[component]
+pub fn LangSelect() -> Element {
+    let mut lang = use_context::<Signal<Lang>>();
+
+    rsx! {
+        select { id: "lang-select",
+            onchange: move |evt: FormEvent| {
+                lang.set(evt.parsed()?);
+                Ok(())
+            },
+            for option_lang in Lang::iter() {
+                option { value: "{option_lang}",
+                    selected: lang() == option_lang,
+                    "{option_lang.label()}"
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn test_all_keys_translated() {
+        for key in Key::iter() {
+            let (cs, en) = (t(key, Lang::Cs), t(key, Lang::En));
+            assert!(!cs.is_empty() && !en.is_empty(), "{key:?} not translated");
+            assert_ne!(cs, en, "{key:?} probably not translated");
+        }
+        assert_eq!(t(Key::Providers, Lang::default()), "Poskytovatelé");
+        assert_eq!(t(Key::Providers, Lang::En), "Providers");
+    }
+}
@@ -0,0 +1,194 @@
+//! Minimal import of trip times from a calendar event (iCalendar, RFC 5545).
+
+use crate::{FormEvent, TripInputData, provider::PRAGUE};
+use anyhow::{Context, Result, bail};
+use dioxus::prelude::*;
+use jiff::{
+    Span, Timestamp,
+    civil::{Date, DateTime, Time},
+    tz::TimeZone,
+};
+
+/// Set begin and end of `input_data` from the first event in `ics`, converted to `tz`.
+pub fn import_event(input_data: TripInputData, ics: &str, tz: &TimeZone) -> Result<TripInputData> {
+    let (begin, end) = parse_event(ics, tz)?;
+    Ok(TripInputData { begin, end, ..input_data })
+}
+
+fn parse_event(ics: &str, tz: &TimeZone) -> Result<(DateTime, DateTime)> {
+    // Long lines are folded by a line break followed by a space or a tab.
+    let unfolded = ics.replace("\r\n", "\n").replace("\n ", "").replace("\n\t", "");
+    let event = unfolded
+        .lines()
+        .skip_while(|line| line.trim() != "BEGIN:VEVENT")
+        .skip(1)
+        .take_while(|line| line.trim() != "END:VEVENT");
+
+    let (mut begin, mut end, mut duration) = (None, None, None);
+    for line in event {
+        let Some((name_params, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut params = name_params.split(';');
+        let name = params.next().unwrap_or_default();
+        let tzid =
+            params.find_map(|param| param.strip_prefix("TZID=")).map(|t| t.trim_matches('"'));
+        let value = value.trim();
+
+        match name {
+            "DTSTART" => begin = Some(parse_datetime(value, tzid, tz).context("parsing DTSTART")?),
+            "DTEND" => end = Some(parse_datetime(value, tzid, tz).context("parsing DTEND")?),
+            "DURATION" => {
+                duration = Some(value.parse::<Span>().context("parsing DURATION")?);
+            },
+            _ => {},
+        }
+    }
+
+    let Some(begin) = begin else {
+        bail!("no event with DTSTART found");
+    };
+    let end = match (end, duration) {
+        (Some(end), _) => end,
+        (None, Some(duration)) => begin.checked_add(duration)?,
+        (None, None) => bail!("event has neither DTEND nor DURATION"),
+    };
+    if end < begin {
+        bail!("event ends before it starts");
+    }
+    Ok((begin, end))
+}
+
+/// Parse date or date-time `value` in time zone `tzid` (UTC if suffixed by Z, floating if
+/// neither) into local time in `tz`.
+fn parse_datetime(value: &str, tzid: Option<&str>, tz: &TimeZone) -> Result<DateTime> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let datetime = DateTime::strptime("%Y%m%dT%H%M%S", utc)?;
+        let timestamp: Timestamp = datetime.to_zoned(TimeZone::UTC)?.timestamp();
+        return Ok(timestamp.to_zoned(tz.clone()).datetime());
+    }
+
+    let datetime = match value.len() {
+        // All-day events have just the date.
+        8 => Date::strptime("%Y%m%d", value)?.to_datetime(Time::midnight()),
+        _ => DateTime::strptime("%Y%m%dT%H%M%S", value)?,
+    };
+    match tzid {
+        Some(tzid) => {
+            let event_tz = TimeZone::get(tzid).with_context(|| format!("unknown TZID {tzid}"))?;
+            Ok(datetime.to_zoned(event_tz)?.with_time_zone(tz.clone()).datetime())
+        },
+        None => Ok(datetime),
+    }
+}
+
+#[component]
+pub fn IcsImport(input_data: Signal<TripInputData>) -> Element {
+    let mut ics = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+
+    let mut import = move |ics: &str| {
+        let current = *input_data.read();
+        match import_event(current, ics, &PRAGUE) {
+            Ok(imported) => {
+                input_data.set(imported);
+                error.set(None);
+            },
+            Err(e) => error.set(Some(format!("{e:#}"))),
+        }
+    };
+    let file_changed = move |evt: FormEvent| async move {
+        let Some(file_engine) = evt.files() else {
+            return;
+        };
+        for file in file_engine.files() {
+            match file_engine.read_file_to_string(&file).await {
+                Some(contents) => import(&contents),
+                None => error.set(Some(format!("cannot read {file}"))),
+            }
+        }
+    };
+
+    rsx! {
+        details {
+            summary { "Import z kalendáře (ICS)" },
+            p {
+                textarea { id: "ics-text",
+                    placeholder: "BEGIN:VEVENT…",
+                    value: "{ics}",
+                    oninput: move |evt| ics.set(evt.value()),
+                }
+            }
+            p {
+                button { onclick: move |_| import(&ics.read()), "Načíst" }
+                " nebo "
+                input { id: "ics-file",
+                    r#type: "file",
+                    accept: ".ics,text/calendar",
+                    onchange: file_changed,
+                }
+            }
+            if let Some(error) = error() {
+                p { class: "unavailable", "Import selhal: {error}" }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::civil::date;
+    use test_log::test;
+
+    const EVENT: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:1234@example.com\r
+SUMMARY:Výlet na chatu s dlouhým názvem\r
+  pokračujícím na dalším řádku\r
+DTSTART;TZID=Europe/Prague:20250603T100000\r
+DTEND;TZID=Europe/Prague:20250603T141500\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_import_event() {
+        let prague = TimeZone::get("Europe/Prague").unwrap();
+        let begin = date(2025, 1, 1).at(8, 0, 0, 0);
+        let input_data = TripInputData::from_times(42.0, begin, begin);
+
+        let imported = import_event(input_data, EVENT, &prague).unwrap();
+        assert_eq!(imported.begin, date(2025, 6, 3).at(10, 0, 0, 0));
+        assert_eq!(imported.end, date(2025, 6, 3).at(14, 15, 0, 0));
+        assert_eq!(imported.km, 42.0, "km are left as they were");
+
+        // UTC and durations, converted to local (summer) time.
+        let utc = "BEGIN:VEVENT\nDTSTART:20250603T080000Z\nDURATION:PT1H30M\nEND:VEVENT\n";
+        let imported = import_event(input_data, utc, &prague).unwrap();
+        assert_eq!(imported.begin, date(2025, 6, 3).at(10, 0, 0, 0));
+        assert_eq!(imported.end, date(2025, 6, 3).at(11, 30, 0, 0));
+    }
+
+    #[test]
+    fn test_import_malformed_event() {
+        let input_data = TripInputData::from_times(
+            1.0,
+            date(2025, 1, 1).at(8, 0, 0, 0),
+            date(2025, 1, 1).at(9, 0, 0, 0),
+        );
+        let error = |ics| import_event(input_data, ics, &TimeZone::UTC).unwrap_err().to_string();
+
+        assert_eq!(error("nonsense"), "no event with DTSTART found");
+        assert_eq!(
+            error("BEGIN:VEVENT\nDTSTART:20250603T080000\nEND:VEVENT"),
+            "event has neither DTEND nor DURATION"
+        );
+        assert_eq!(error("BEGIN:VEVENT\nDTSTART:2025-06-03\nEND:VEVENT"), "parsing DTSTART");
+        assert_eq!(
+            error("BEGIN:VEVENT\nDTSTART:20250603T080000\nDTEND:20250603T070000\nEND:VEVENT"),
+            "event ends before it starts"
+        );
+    }
+}
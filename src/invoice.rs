@@ -0,0 +1,203 @@
+//! Compare computed price breakdown with the invoice the provider actually issued.
+//!
+//! The invoice is pasted as text, one item per line in the form `<description> <amount> Kč`,
+//! e.g. `extra za 5 km 47,45 Kč`. Amounts may use decimal comma or point and spaces to separate
+//! thousands. A line starting with `Celkem` is the invoice total, other lines are ignored.
+
+use crate::provider::CalculationResult;
+use anyhow::{Context, Result, bail};
+use dioxus::prelude::*;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Amounts closer than this are considered equal.
+const TOLERANCE_CZK: f64 = 0.005;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invoice {
+    pub items: Vec<(String, f64)>,
+    pub total_czk: Option<f64>,
+}
+
+/// How a single line of the computed breakdown or the invoice compares.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineDiff {
+    Same { name: String, czk: f64 },
+    Differs { name: String, computed_czk: f64, invoiced_czk: f64 },
+    OnlyComputed { name: String, czk: f64 },
+    OnlyInvoiced { name: String, czk: f64 },
+}
+
+impl LineDiff {
+    fn is_same(&self) -> bool {
+        matches!(self, LineDiff::Same { .. })
+    }
+}
+
+pub fn parse_invoice(text: &str) -> Result<Invoice> {
+    static ITEM_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^(.+?)\s+(-?[0-9][0-9 ]*(?:[,.][0-9]+)?)\s*Kč$").expect("valid regex")
+    });
+
+    let mut invoice = Invoice { items: vec![], total_czk: None };
+    for line in text.lines().map(str::trim) {
+        let Some(captures) = ITEM_RE.captures(line) else {
+            continue;
+        };
+        let name = captures[1].trim_end_matches(':').trim();
+        let amount = captures[2].replace(' ', "").replace(',', ".");
+        let czk: f64 = amount.parse().with_context(|| format!("parsing amount of {line:?}"))?;
+
+        if name.to_lowercase().starts_with("celkem") {
+            invoice.total_czk = Some(czk);
+        } else {
+            invoice.items.push((name.to_string(), czk));
+        }
+    }
+    if invoice.items.is_empty() {
+        bail!("no invoice items found, expected lines like \"extra za 5 km 47,45 Kč\"");
+    }
+    Ok(invoice)
+}
+
+/// Pair invoice items with computed components by (case-insensitive) name, computed ones first.
+pub fn diff(result: &CalculationResult, invoice: &Invoice) -> Vec<LineDiff> {
+    let mut invoiced: Vec<_> = invoice.items.iter().map(Some).collect();
+    let mut lines = vec![];
+
+    for component in &result.components {
+        let found = invoiced.iter_mut().find(|item| {
+            item.is_some_and(|(name, _)| name.to_lowercase() == component.name.to_lowercase())
+        });
+        let line = match found.and_then(Option::take) {
+            Some((_, invoiced_czk)) if (invoiced_czk - component.czk).abs() < TOLERANCE_CZK => {
+                LineDiff::Same { name: component.name.clone(), czk: component.czk }
+            },
+            Some((_, invoiced_czk)) => LineDiff::Differs {
+                name: component.name.clone(),
+                computed_czk: component.czk,
+                invoiced_czk: *invoiced_czk,
+            },
+            None => LineDiff::OnlyComputed { name: component.name.clone(), czk: component.czk },
+        };
+        lines.push(line);
+    }
+    for (name, czk) in invoiced.into_iter().flatten() {
+        lines.push(LineDiff::OnlyInvoiced { name: name.clone(), czk: *czk });
+    }
+    lines
+}
+
+#[component]
+pub fn InvoiceCompare(provider: String, result: CalculationResult) -> Element {
+    let mut text = use_signal(String::new);
+
+    let comparison = (!text.read().trim().is_empty()).then(|| {
+        let invoice = parse_invoice(&text.read())?;
+        Ok::<_, anyhow::Error>((diff(&result, &invoice), invoice.total_czk))
+    });
+    let computed_total_czk = result.total_czk();
+
+    rsx! {
+        details {
+            summary { "Porovnat s fakturou" },
+            textarea { id: "provider-{provider}-invoice",
+                placeholder: "extra za 5 km 47,45 Kč",
+                rows: 6,
+                value: "{text}",
+                oninput: move |evt| text.set(evt.value()),
+            }
+            match comparison {
+                None => rsx! {},
+                Some(Err(e)) => rsx! { p { class: "unavailable", "Fakturu nelze načíst: {e:#}" } },
+                Some(Ok((lines, invoiced_total_czk))) => rsx! {
+                    table { class: "invoice-diff",
+                        // Invoices are in crowns, whichever currency prices are shown in.
+                        caption { "částky v Kč jako na faktuře" }
+                        tr { th { "položka" } th { "vypočteno" } th { "na faktuře" } }
+                        for line in lines.iter() {
+                            tr { class: if !line.is_same() { "differs" },
+                                match line {
+                                    LineDiff::Same { name, czk } => rsx! {
+                                        td { "{name}" } td { "{czk:.2} Kč" } td { "{czk:.2} Kč" }
+                                    },
+                                    LineDiff::Differs { name, computed_czk, invoiced_czk } => rsx! {
+                                        td { "{name}" } td { "{computed_czk:.2} Kč" } td { "{invoiced_czk:.2} Kč" }
+                                    },
+                                    LineDiff::OnlyComputed { name, czk } => rsx! {
+                                        td { "{name}" } td { "{czk:.2} Kč" } td { "chybí" }
+                                    },
+                                    LineDiff::OnlyInvoiced { name, czk } => rsx! {
+                                        td { "{name}" } td { "chybí" } td { "{czk:.2} Kč" }
+                                    },
+                                }
+                            }
+                        }
+                        if let Some(invoiced_total_czk) = invoiced_total_czk {
+                            tr {
+                                class: if (invoiced_total_czk - computed_total_czk).abs() >= TOLERANCE_CZK { "differs" },
+                                th { "celkem" } th { "{computed_total_czk:.2} Kč" } th { "{invoiced_total_czk:.2} Kč" }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    const INVOICE: &str = "
+        Faktura č. 2025-0042
+        2 hodiny + 10 km     249,00 Kč
+        Extra za 5 km:        52,45 Kč
+        servisní poplatek     20 Kč
+        Celkem               321,45 Kč
+    ";
+
+    #[test]
+    fn test_parse_invoice() {
+        let invoice = parse_invoice(INVOICE).unwrap();
+        assert_eq!(
+            invoice.items,
+            [
+                ("2 hodiny + 10 km".to_string(), 249.0),
+                ("Extra za 5 km".to_string(), 52.45),
+                ("servisní poplatek".to_string(), 20.0),
+            ]
+        );
+        assert_eq!(invoice.total_czk, Some(321.45));
+
+        let invoice = parse_invoice("pojištění 2 dny 1 398,5 Kč").unwrap();
+        assert_eq!(invoice.items, [("pojištění 2 dny".to_string(), 1398.5)]);
+
+        assert!(parse_invoice("Faktura bez položek").is_err());
+    }
+
+    #[test]
+    fn test_diff_invoice() {
+        let mut result = CalculationResult::new("Legend (Fabia)");
+        result.add_component(249.0, "2 hodiny + 10 km");
+        result.add_component(47.45, "extra za 5 km");
+        result.add_component(199.0, "pojištění 1 den");
+
+        let invoice = parse_invoice(INVOICE).unwrap();
+        assert_eq!(
+            diff(&result, &invoice),
+            [
+                LineDiff::Same { name: "2 hodiny + 10 km".into(), czk: 249.0 },
+                LineDiff::Differs {
+                    name: "extra za 5 km".into(),
+                    computed_czk: 47.45,
+                    invoiced_czk: 52.45,
+                },
+                LineDiff::OnlyComputed { name: "pojištění 1 den".into(), czk: 199.0 },
+                LineDiff::OnlyInvoiced { name: "servisní poplatek".into(), czk: 20.0 },
+            ]
+        );
+    }
+}
@@ -1,24 +1,63 @@
-use crate::provider::{
-    CalculationResult, Provider, ProviderKind,
-    bolt::Bolt,
-    car4way::{Car4way, Car4wayInput},
+use crate::{
+    currency::{CurrencySelect, DisplayCurrency, convert, group_thousands, use_currency},
+    distance::DistancePicker,
+    export::CsvExport,
+    feedback::{Feedback, FeedbackControls, FeedbackExport},
+    history::History,
+    i18n::{Key, LangSelect, over_budget, t, use_lang, weekday_names},
+    ics::IcsImport,
+    invoice::InvoiceCompare,
+    provider::{
+        CalculationResult, CheapestSummary, Direction, Discount, DiscountKind, EPSILON_CZK, PRAGUE,
+        Provider, ReturnLocation, Rounding, anytime::Anytime, baseline_delta_czk, bike::Bike,
+        bolt::Bolt, car4way::Car4way, cheapest_names, compare_all, elapsed, move_provider,
+        now_in_prague, owncar::OwnCar, pid::Pid, sort_by_ranking, taxi::Taxi, uber::Uber,
+        walk::Walk, wall_clock,
+    },
+    share::{ShareLink, use_shared_trip},
+    state::StateJson,
+    sweep::{CostCurve, SweepView},
+    theme::ThemeToggle,
 };
 use dioxus::prelude::*;
-use jiff::{RoundMode, ToSpan, Unit, Zoned, ZonedRound, civil::DateTime};
-use tracing::debug;
+use jiff::{
+    RoundMode, SignedDuration, Span, SpanRound, ToSpan, Unit, Zoned, ZonedRound,
+    civil::{Date, DateTime, Time, Weekday},
+};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use tracing::{debug, error};
 
+pub mod currency;
+pub mod distance;
+pub mod export;
+pub mod feedback;
+pub mod history;
+pub mod i18n;
+pub mod ics;
+pub mod invoice;
 pub mod provider;
+pub mod share;
+pub mod state;
+pub mod sweep;
+pub mod theme;
 
 type FormEvent = Event<FormData>;
 
 static CSS: Asset = asset!("/assets/main.css");
 
+/// Kilometers added or subtracted by the quick buttons next to the km input.
+const KM_STEPS: [f64; 2] = [1.0, 10.0];
+
 fn main() {
     dioxus::launch(App);
 }
 
 #[component]
 fn App() -> Element {
+    use_context_provider(|| Signal::new(i18n::Lang::default()));
+    use_context_provider(|| Signal::new(DisplayCurrency::default()));
+
     rsx! {
         document::Stylesheet { href: CSS }
         Title {}
@@ -32,6 +71,7 @@ fn Title() -> Element {
 
     rsx! {
         div { id: "title", class: "top-section",
+            span { id: "header-controls", LangSelect {} " " CurrencySelect {} " " ThemeToggle {} }
             h1 { "{title}" }
         }
     }
@@ -40,82 +80,494 @@ fn Title() -> Element {
 #[component]
 fn MainView() -> Element {
     debug!("MainView rendering...");
+    let lang = use_lang();
     let input_data = use_signal(|| TripInputData::new().expect("can construct TripInputData"));
+    // Stops of the trip after the first segment, see TripInputData::itinerary().
+    let further_segments = use_signal(Vec::<TripInputData>::new);
+    // Whether the distance of the trip is being looked up, see DistancePicker.
+    let computing_km = use_signal(|| false);
 
     let bolt_enabled = use_signal(|| true);
     let bolt = use_signal(Bolt::default);
-    let bolt = Provider::new(bolt_enabled, ProviderKind::Bolt(bolt));
+    let bolt = Provider::new(bolt_enabled, bolt);
+
+    // Disabled until its hand-written prices are checked, see provider-data/anytime/README.md.
+    let anytime_enabled = use_signal(|| false);
+    let anytime = use_signal(Anytime::default);
+    let anytime = Provider::new(anytime_enabled, anytime);
 
     let car4way_enabled = use_signal(|| true);
     let car4way = use_signal(Car4way::default);
-    let car4way = Provider::new(car4way_enabled, ProviderKind::Car4way(car4way));
+    let car4way = Provider::new(car4way_enabled, car4way);
+
+    let walk_enabled = use_signal(|| true);
+    let walk = use_signal(Walk::default);
+    let walk = Provider::new(walk_enabled, walk);
+
+    let own_car_enabled = use_signal(|| true);
+    let own_car = use_signal(OwnCar::default);
+    let own_car = Provider::new(own_car_enabled, own_car);
+
+    let pid_enabled = use_signal(|| true);
+    let pid = use_signal(Pid::default);
+    let pid = Provider::new(pid_enabled, pid);
+
+    let bike_enabled = use_signal(|| true);
+    let bike = use_signal(Bike::default);
+    let bike = Provider::new(bike_enabled, bike);
 
-    let providers = [bolt, car4way];
+    let taxi_enabled = use_signal(|| true);
+    let taxi = use_signal(Taxi::default);
+    let taxi = Provider::new(taxi_enabled, taxi);
+
+    let uber_enabled = use_signal(|| true);
+    let uber = use_signal(Uber::default);
+    let uber = Provider::new(uber_enabled, uber);
+
+    let mut show_disabled = use_signal(|| false);
+    let mut show_without_airport = use_signal(|| false);
+    let mut show_without_packages = use_signal(|| false);
+    // Raw state of providers, for developers and bug reports.
+    let mut show_debug = use_signal(|| false);
+    let mut sort_by_price = use_signal(|| true);
+    let feedback = use_signal(Vec::<Feedback>::new);
+    // Name of the provider others are compared to, if chosen.
+    let baseline = use_signal(|| None::<String>);
+
+    let providers = [anytime, bike, bolt, car4way, own_car, pid, taxi, uber, walk];
+    use_shared_trip(input_data, &providers);
+    // Order chosen by the user, reordering shouldn't recompute the ranking below.
+    let mut ordered = use_signal(|| providers.to_vec());
+    let results = use_memo({
+        let providers = providers.clone();
+        move || compare_all(&providers, &input_data.read().itinerary(&further_segments.read()))
+    });
+    let cheapest: Vec<_> =
+        cheapest_names(&results.read()).into_iter().map(str::to_string).collect();
+    let summary = CheapestSummary::new(&results.read());
+    let shown =
+        if sort_by_price() { sort_by_ranking(&ordered.read(), &results.read()) } else { ordered() };
 
     rsx! {
-        TripInput { input_data },
+        TripInput { input_data, further_segments, computing_km },
+        ShareLink { input_data, further_segments, providers: providers.to_vec() },
+        History { input_data, further_segments },
+        SweepView { providers: ordered(), input_data, further_segments },
+        CostCurve { providers: ordered(), input_data, further_segments },
         div { id: "providers", class: "top-section",
-            h2 { "Poskytovatelé" },
+            h2 { {t(Key::Providers, lang)} },
+            if let Some(summary) = summary {
+                p { class: "summary", "{summary.text(lang)}" }
+            }
+            CsvExport { results },
+            p {
+                input { id: "show-disabled",
+                    r#type: "checkbox",
+                    checked: show_disabled,
+                    onchange: move |evt: FormEvent| show_disabled.set(evt.checked()),
+                }
+                label { for: "show-disabled", " {t(Key::ShowDisabled, lang)}" },
+            }
+            p {
+                input { id: "sort-by-price",
+                    r#type: "checkbox",
+                    checked: sort_by_price,
+                    onchange: move |evt: FormEvent| sort_by_price.set(evt.checked()),
+                }
+                label { for: "sort-by-price", " {t(Key::SortByPrice, lang)}" },
+            }
+            p {
+                input { id: "show-without-packages",
+                    r#type: "checkbox",
+                    checked: show_without_packages,
+                    onchange: move |evt: FormEvent| show_without_packages.set(evt.checked()),
+                }
+                label { for: "show-without-packages", " {t(Key::ShowWithoutPackages, lang)}" },
+            }
+            if input_data.read().has_airport() {
+                p {
+                    input { id: "show-without-airport",
+                        r#type: "checkbox",
+                        checked: show_without_airport,
+                        onchange: move |evt: FormEvent| show_without_airport.set(evt.checked()),
+                    }
+                    label { for: "show-without-airport", " {t(Key::CompareWithoutAirport, lang)}" },
+                }
+            }
+            p {
+                input { id: "show-debug",
+                    r#type: "checkbox",
+                    checked: show_debug,
+                    onchange: move |evt: FormEvent| show_debug.set(evt.checked()),
+                }
+                label { for: "show-debug", " {t(Key::ShowDebug, lang)}" },
+            }
             div { id: "providers-wrapper",
-                for provider in providers {
-                    ProviderSection { provider, input_data },
+                for (index, provider) in shown.into_iter().enumerate() {
+                    ProviderSection {
+                        key: "{provider.name()}",
+                        is_cheapest: cheapest.iter().any(|name| name == provider.name()),
+                        delta_czk: baseline().and_then(|baseline| {
+                            baseline_delta_czk(&results.read(), &baseline, provider.name())
+                        }),
+                        baseline,
+                        provider,
+                        input_data,
+                        further_segments,
+                        computing_km: computing_km(),
+                        show_disabled: show_disabled(),
+                        show_without_airport: show_without_airport(),
+                        show_without_packages: show_without_packages(),
+                        show_debug: show_debug(),
+                        feedback,
+                        on_move: (!sort_by_price()).then_some(EventHandler::new(move |direction| {
+                            move_provider(&mut ordered.write(), index, direction)
+                        })),
+                    },
                 }
             }
         }
+        FeedbackExport { feedback }
+        StateJson { input_data, further_segments, providers: providers.to_vec(), results }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct TripInputData {
     km: f64,
     begin: DateTime,
     end: DateTime,
+    /// When the car gets reserved ahead of [`TripInputData::begin`], if at all.
+    reservation_begin: Option<DateTime>,
+    rounding: Rounding,
+    return_location: ReturnLocation,
+    /// Shared by all providers where fuel is not included in the price.
+    fuel_price_czk_per_l: f64,
+    /// Minutes at the end of the rental the car is just parked, some tariffs bill them cheaper.
+    idle_minutes: i64,
+    /// Expected time actually spent driving, for providers billing just that. `None` for all of
+    /// the time between [`TripInputData::begin`] and [`TripInputData::end`].
+    driving_minutes: Option<i64>,
+    /// Maximum the user is willing to pay for the trip, if set.
+    budget_czk: Option<f64>,
+    /// Whether the trip ends at (enters) the airport.
+    airport_enter: bool,
+    /// Whether the trip begins at (leaves) the airport.
+    airport_leave: bool,
+    /// People on the trip. A car price is split among them, public transport fares are multiplied.
+    passengers: u32,
+    /// Whether [`TripInputData::km`] is one way only and the trip goes there and back.
+    round_trip: bool,
+    /// Promo credit subtracted from every provider's price.
+    #[serde(default)]
+    discount: Discount,
+    /// Whether providers may use packages, disabled only to show what they save.
+    #[serde(skip, default = "packages_default")]
+    packages: bool,
+}
+
+fn packages_default() -> bool {
+    true
 }
 
 impl TripInputData {
     fn new() -> Result<Self, RenderError> {
-        let in_five_mins = Zoned::now()
-            .round(ZonedRound::new().smallest(Unit::Minute).mode(RoundMode::Ceil).increment(5))?;
+        Ok(Self::starting_after(&now_in_prague())?)
+    }
+
+    /// Default trip beginning at the next whole 5 minutes after `now`, in Prague wall-clock time.
+    fn starting_after(now: &Zoned) -> Result<Self, jiff::Error> {
+        let in_five_mins = next_five_minutes(now)?;
         let end = &in_five_mins + 2.hours();
         let end = &end + 10.minutes();
 
-        Ok(Self { km: 12.0, begin: in_five_mins.datetime(), end: end.datetime() })
+        Ok(Self::from_times(12.0, in_five_mins.datetime(), end.datetime()))
+    }
+
+    /// Trip with given distance and time window, everything else at defaults.
+    fn from_times(km: f64, begin: DateTime, end: DateTime) -> Self {
+        Self {
+            km,
+            begin,
+            end,
+            reservation_begin: None,
+            rounding: Rounding::default(),
+            return_location: ReturnLocation::default(),
+            fuel_price_czk_per_l: 38.0,
+            idle_minutes: 0,
+            driving_minutes: None,
+            budget_czk: None,
+            airport_enter: false,
+            airport_leave: false,
+            passengers: 1,
+            round_trip: false,
+            discount: Discount::default(),
+            packages: true,
+        }
+    }
+
+    /// Kilometers without any rental time: providers charge just the distance-based part.
+    fn is_km_only(&self) -> bool {
+        self.begin == self.end && self.km > 0.0
+    }
+
+    /// User-facing reason why no provider can calculate the trip, if it is invalid.
+    fn invalid_reason(&self) -> Option<Key> {
+        (self.end < self.begin).then_some(Key::EndBeforeBegin)
+    }
+
+    /// Total rental time for display, or an error if the trip ends before it begins.
+    fn total_time(&self) -> Result<String, Key> {
+        if let Some(reason) = self.invalid_reason() {
+            return Err(reason);
+        }
+        Ok(format_duration(self.end - self.begin))
+    }
+
+    fn has_airport(&self) -> bool {
+        self.airport_enter || self.airport_leave
+    }
+
+    /// The trip as providers bill it, i.e. with kilometers and driving minutes doubled for a round
+    /// trip.
+    fn effective(self) -> Self {
+        if !self.round_trip {
+            return self;
+        }
+        let driving_minutes = self.driving_minutes.map(|minutes| minutes * 2);
+        Self { km: self.km * 2.0, driving_minutes, round_trip: false, ..self }
+    }
+
+    /// The same trip, but pretending it neither enters nor leaves the airport.
+    fn without_airport(self) -> Self {
+        Self { airport_enter: false, airport_leave: false, ..self }
+    }
+
+    /// Quick scenario: pick the car up in the evening, return it the next morning.
+    fn overnight(self, evening: Date) -> Self {
+        const OVERNIGHT_KM: f64 = 5.0;
+
+        let begin = evening.at(19, 0, 0, 0);
+        let end = (evening + 1.day()).at(7, 0, 0, 0);
+        Self { km: OVERNIGHT_KM, begin, end, ..self }
+    }
+
+    /// Preset: from the next whole 5 minutes after `now` for `span`, days of which keep the time of
+    /// day across DST transitions.
+    fn starting_now_for(self, now: &Zoned, span: Span) -> Result<Self, jiff::Error> {
+        let begin = next_five_minutes(now)?;
+        let end = begin.checked_add(span)?;
+        Ok(Self { begin: begin.datetime(), end: end.datetime(), ..self })
+    }
+
+    /// Preset: the upcoming weekend after `now`, Friday 16:00 to Monday 10:00 in Prague, which
+    /// weekend packages cover.
+    fn weekend(self, now: &Zoned) -> Result<Self, jiff::Error> {
+        const FRIDAY_AFTERNOON: Time = Time::constant(16, 0, 0, 0);
+
+        let now = now.with_time_zone(PRAGUE.clone());
+        let friday = if now.weekday() == Weekday::Friday && now.time() < FRIDAY_AFTERNOON {
+            now.date()
+        } else {
+            now.date().nth_weekday(1, Weekday::Friday)?
+        };
+        let begin = friday.to_datetime(FRIDAY_AFTERNOON);
+        let end = friday.checked_add(3.days())?.at(10, 0, 0, 0);
+        Ok(Self { begin, end, ..self })
     }
+
+    /// Quick scenario: a ride between the city center and Václav Havel Airport Prague, entering it
+    /// if `to_airport`, leaving it otherwise. Times are left as they are.
+    fn airport(self, to_airport: bool) -> Self {
+        /// Typical road distance from the center to the terminals.
+        const AIRPORT_KM: f64 = 17.0;
+
+        Self { km: AIRPORT_KM, airport_enter: to_airport, airport_leave: !to_airport, ..self }
+    }
+
+    /// The trip segment by segment: `further` ones only give their distance and times, the rest is
+    /// shared with this first one.
+    fn itinerary(self, further: &[TripInputData]) -> Vec<Self> {
+        let further = further.iter().map(|segment| Self {
+            km: segment.km,
+            begin: segment.begin,
+            end: segment.end,
+            ..self
+        });
+        [self].into_iter().chain(further).collect()
+    }
+
+    /// The trip `step` kilometers longer (shorter if negative), but not below zero.
+    fn with_km_step(self, step: f64) -> Self {
+        Self { km: (self.km + step).max(0.0), ..self }
+    }
+
+    /// Move the trip to the nearest `weekday` (possibly today), keeping time of day and duration.
+    fn with_begin_weekday(self, weekday: Weekday) -> Self {
+        let shift = i64::from(weekday.since(self.begin.weekday())).days();
+        Self {
+            begin: self.begin + shift,
+            end: self.end + shift,
+            reservation_begin: self.reservation_begin.map(|reservation| reservation + shift),
+            ..self
+        }
+    }
+}
+
+/// `now` rounded up to whole 5 minutes, in Prague.
+fn next_five_minutes(now: &Zoned) -> Result<Zoned, jiff::Error> {
+    now.with_time_zone(PRAGUE.clone())
+        .round(ZonedRound::new().smallest(Unit::Minute).mode(RoundMode::Ceil).increment(5))
 }
 
 #[component]
-fn TripInput(input_data: Signal<TripInputData>) -> Element {
+fn TripInput(
+    input_data: Signal<TripInputData>,
+    further_segments: Signal<Vec<TripInputData>>,
+    computing_km: Signal<bool>,
+) -> Element {
     debug!("TripInput rendering, input_data: {:?}.", input_data);
+    let lang = use_lang();
 
     let km_changed = move |evt: FormEvent| {
         input_data.write().km = evt.parsed()?;
         Ok(())
     };
+    // The inputs have no time zone, their values are Prague wall-clock times.
     let begin_changed = move |evt: FormEvent| {
-        input_data.write().begin = evt.parsed()?;
+        input_data.write().begin = wall_clock(evt.parsed()?);
         Ok(())
     };
     let end_changed = move |evt: FormEvent| {
-        input_data.write().end = evt.parsed()?;
+        input_data.write().end = wall_clock(evt.parsed()?);
+        Ok(())
+    };
+    let reservation_changed = move |evt: FormEvent| {
+        let value = evt.value();
+        input_data.write().reservation_begin =
+            if value.is_empty() { None } else { Some(wall_clock(value.parse()?)) };
+        Ok(())
+    };
+
+    let weekday_changed = move |evt: FormEvent| {
+        let weekday = Weekday::from_monday_zero_offset(evt.parsed()?)?;
+        input_data.with_mut(|input_data| *input_data = input_data.with_begin_weekday(weekday));
         Ok(())
     };
 
-    let total_time = input_data.with(|input_data| input_data.end - input_data.begin);
+    let overnight_clicked = move |_| {
+        input_data
+            .with_mut(|input_data| *input_data = input_data.overnight(now_in_prague().date()));
+    };
+
+    let mut preset_clicked = move |preset: fn(TripInputData, &Zoned) -> Result<_, jiff::Error>| {
+        let current = *input_data.read();
+        match preset(current, &now_in_prague()) {
+            Ok(preset) => input_data.set(preset),
+            Err(e) => error!("applying a time preset: {e}"),
+        }
+    };
+
+    let mut airport_clicked = move |to_airport| {
+        input_data.with_mut(|input_data| *input_data = input_data.airport(to_airport));
+    };
+
+    let rounding_changed = move |evt: FormEvent| {
+        input_data.write().rounding = evt.parsed()?;
+        Ok(())
+    };
+
+    let idle_minutes_changed = move |evt: FormEvent| {
+        input_data.write().idle_minutes = evt.parsed()?;
+        Ok(())
+    };
+    let driving_minutes_changed = move |evt: FormEvent| {
+        let value = evt.value();
+        input_data.write().driving_minutes =
+            if value.is_empty() { None } else { Some(value.parse::<i64>()?.max(0)) };
+        Ok(())
+    };
+    let budget_changed = move |evt: FormEvent| {
+        let value = evt.value();
+        input_data.write().budget_czk = if value.is_empty() { None } else { Some(value.parse()?) };
+        Ok(())
+    };
+    let discount_kind_changed = move |evt: FormEvent| {
+        input_data.write().discount.kind = evt.parsed()?;
+        Ok(())
+    };
+    let discount_amount_changed = move |evt: FormEvent| {
+        input_data.write().discount.amount = evt.parsed::<f64>()?.max(0.0);
+        Ok(())
+    };
+    let passengers_changed = move |evt: FormEvent| {
+        input_data.write().passengers = evt.parsed::<u32>()?.max(1);
+        Ok(())
+    };
+    let round_trip_changed = move |evt: FormEvent| {
+        input_data.write().round_trip = evt.checked();
+    };
+    let fuel_price_changed = move |evt: FormEvent| {
+        input_data.write().fuel_price_czk_per_l = evt.parsed()?;
+        Ok(())
+    };
+
+    let return_location_changed = move |evt: FormEvent| {
+        input_data.write().return_location = evt.parsed()?;
+        Ok(())
+    };
+
+    let km_steps = KM_STEPS.iter().rev().map(|step| -step).chain(KM_STEPS);
+    let km_step_label = move |step: f64| {
+        let action = if step > 0.0 { t(Key::AddKm, lang) } else { t(Key::SubtractKm, lang) };
+        format!("{action} {} km", step.abs())
+    };
+
+    let total_time = input_data.read().total_time();
+    let discount = input_data.read().discount;
+    let begin_weekday = input_data.read().begin.weekday();
 
     rsx! {
         div { id: "trip", class: "top-section",
-            h2 { "Cesta" },
+            h2 { {t(Key::Trip, lang)} },
             p {
-                label { for: "input-kilometers", "Počet km " },
+                label { for: "input-kilometers", "{t(Key::Kilometers, lang)} " },
                 input { id: "input-kilometers",
                     r#type: "number",
                     value: input_data.read().km,
                     onchange: km_changed,
                     min: 0,
                 },
+                for step in km_steps {
+                    " ",
+                    button { class: "km-step",
+                        aria_label: km_step_label(step),
+                        onclick: move |_| {
+                            input_data.with_mut(|input_data| *input_data = input_data.with_km_step(step));
+                        },
+                        "{step:+}"
+                    }
+                }
+                " ",
+                input { id: "input-round-trip",
+                    r#type: "checkbox",
+                    checked: input_data.read().round_trip,
+                    onchange: round_trip_changed,
+                }
+                label { for: "input-round-trip", " {t(Key::RoundTrip, lang)}" },
+            },
+            DistancePicker { input_data, computing: computing_km },
+            p {
+                label { for: "input-passengers", "{t(Key::Passengers, lang)} " },
+                input { id: "input-passengers",
+                    r#type: "number",
+                    value: input_data.read().passengers,
+                    onchange: passengers_changed,
+                    min: 1,
+                },
             },
             p {
-                label { for: "input-begin-time", "Začátek " },
+                label { for: "input-begin-time", "{t(Key::Begin, lang)} " },
                 input { id: "input-begin-time",
                     r#type: "datetime-local",
                     value: input_data.read().begin.to_string(),
@@ -123,7 +575,7 @@ fn TripInput(input_data: Signal<TripInputData>) -> Element {
                 },
             },
             p {
-                label { for: "input-end-time", "Konec " },
+                label { for: "input-end-time", "{t(Key::End, lang)} " },
                 input { id: "input-end-time",
                     r#type: "datetime-local",
                     value: input_data.read().end.to_string(),
@@ -131,56 +583,616 @@ fn TripInput(input_data: Signal<TripInputData>) -> Element {
                 },
             },
             p {
-                "Celkový čas: {total_time:#}"
+                label { for: "input-reservation-time", "{t(Key::ReservationFrom, lang)} " },
+                input { id: "input-reservation-time",
+                    r#type: "datetime-local",
+                    value: input_data.read().reservation_begin.map(|begin| begin.to_string()),
+                    onchange: reservation_changed,
+                },
+            },
+            p {
+                "{t(Key::QuickChoice, lang)}: ",
+                button { onclick: overnight_clicked, {t(Key::Overnight, lang)} },
+                " ",
+                button { onclick: move |_| airport_clicked(true), {t(Key::ToAirport, lang)} },
+                " ",
+                button { onclick: move |_| airport_clicked(false), {t(Key::FromAirport, lang)} },
+            },
+            p {
+                "{t(Key::TimePresets, lang)}: ",
+                button {
+                    onclick: move |_| preset_clicked(|trip, now| trip.starting_now_for(now, 1.hour())),
+                    {t(Key::NowForAnHour, lang)}
+                },
+                " ",
+                button {
+                    onclick: move |_| preset_clicked(|trip, now| trip.starting_now_for(now, 1.day())),
+                    {t(Key::WholeDay, lang)}
+                },
+                " ",
+                button { onclick: move |_| preset_clicked(TripInputData::weekend), {t(Key::Weekend, lang)} },
+            },
+            p {
+                label { for: "input-begin-weekday", "{t(Key::BeginWeekday, lang)} " },
+                select { id: "input-begin-weekday",
+                    onchange: weekday_changed,
+                    for (offset, weekday_name) in weekday_names(lang).into_iter().enumerate() {
+                        option { value: "{offset}",
+                            selected: begin_weekday.to_monday_zero_offset() as usize == offset,
+                            "{weekday_name}"
+                        }
+                    }
+                },
+            },
+            p {
+                label { for: "input-idle-minutes", "{t(Key::IdleMinutes, lang)} " },
+                input { id: "input-idle-minutes",
+                    r#type: "number",
+                    value: input_data.read().idle_minutes,
+                    onchange: idle_minutes_changed,
+                    min: 0,
+                },
+            },
+            p {
+                label { for: "input-driving-minutes", "{t(Key::DrivingMinutes, lang)} " },
+                input { id: "input-driving-minutes",
+                    r#type: "number",
+                    placeholder: t(Key::WholeTime, lang),
+                    value: input_data.read().driving_minutes.map(|minutes| minutes.to_string()),
+                    onchange: driving_minutes_changed,
+                    min: 0,
+                },
+            },
+            p {
+                label { for: "input-budget", "{t(Key::Budget, lang)} " },
+                input { id: "input-budget",
+                    r#type: "number",
+                    placeholder: t(Key::Unlimited, lang),
+                    value: input_data.read().budget_czk.map(|czk| czk.to_string()),
+                    onchange: budget_changed,
+                    min: 0,
+                },
+            },
+            p {
+                label { for: "input-discount-kind", "{t(Key::Discount, lang)} " },
+                select { id: "input-discount-kind",
+                    onchange: discount_kind_changed,
+                    for kind in DiscountKind::iter() {
+                        option { value: "{kind}",
+                            selected: discount.kind == kind,
+                            "{kind.label(lang)}"
+                        }
+                    }
+                },
+                if discount.kind != DiscountKind::None {
+                    " "
+                    input { id: "input-discount-amount",
+                        r#type: "number",
+                        value: discount.amount,
+                        onchange: discount_amount_changed,
+                        min: 0,
+                        max: if discount.kind == DiscountKind::Percent { "100" },
+                    },
+                }
+            },
+            p {
+                label { for: "input-fuel-price", "{t(Key::FuelPrice, lang)} " },
+                input { id: "input-fuel-price",
+                    r#type: "number",
+                    value: input_data.read().fuel_price_czk_per_l,
+                    onchange: fuel_price_changed,
+                    min: 0,
+                    step: 0.1,
+                },
+            },
+            p {
+                label { for: "input-return-location", "{t(Key::CarReturn, lang)} " },
+                select { id: "input-return-location",
+                    onchange: return_location_changed,
+                    for return_location in ReturnLocation::iter() {
+                        option { value: "{return_location}",
+                            selected: input_data.read().return_location == return_location,
+                            "{return_location.label(lang)}"
+                        }
+                    }
+                },
+            },
+            p {
+                input { id: "input-airport-leave",
+                    r#type: "checkbox",
+                    checked: input_data.read().airport_leave,
+                    onchange: move |evt: FormEvent| input_data.write().airport_leave = evt.checked(),
+                }
+                label { for: "input-airport-leave", " {t(Key::FromAirport, lang)} " },
+                input { id: "input-airport-enter",
+                    r#type: "checkbox",
+                    checked: input_data.read().airport_enter,
+                    onchange: move |evt: FormEvent| input_data.write().airport_enter = evt.checked(),
+                }
+                label { for: "input-airport-enter", " {t(Key::ToAirport, lang)}" },
+            },
+            p {
+                label { for: "input-rounding", "{t(Key::Rounding, lang)} " },
+                select { id: "input-rounding",
+                    onchange: rounding_changed,
+                    for rounding in Rounding::iter() {
+                        option { value: "{rounding}",
+                            selected: input_data.read().rounding == rounding,
+                            "{rounding.label(lang)}"
+                        }
+                    }
+                },
+            },
+            match total_time {
+                Ok(total_time) => rsx! { p { "{t(Key::TotalTime, lang)}: {total_time}" } },
+                Err(error) => rsx! { p { class: "unavailable", "{t(Key::TotalTime, lang)}: {t(error, lang)}" } },
+            }
+            if input_data.read().is_km_only() {
+                p { class: "note", {t(Key::KmOnlyNote, lang)} }
             }
+            FurtherSegments { input_data, further_segments }
+            IcsImport { input_data }
         },
     }
 }
 
+/// Editor of the segments following the main one, each with own distance and times.
+#[component]
+fn FurtherSegments(
+    input_data: Signal<TripInputData>,
+    further_segments: Signal<Vec<TripInputData>>,
+) -> Element {
+    let lang = use_lang();
+    let add_clicked = move |_| {
+        let previous_end = further_segments.read().last().unwrap_or(&input_data.read()).end;
+        let begin = previous_end + 1.hour();
+        further_segments.write().push(TripInputData::from_times(0.0, begin, begin + 1.hour()));
+    };
+
+    rsx! {
+        if !further_segments.read().is_empty() {
+            h3 { {t(Key::FurtherSegments, lang)} }
+        }
+        for (index, segment) in further_segments().into_iter().enumerate() {
+            p { key: "{index}",
+                "{index + 2}. {t(Key::Segment, lang)}: ",
+                label { for: "input-segment-{index}-kilometers", "km " },
+                input { id: "input-segment-{index}-kilometers",
+                    r#type: "number",
+                    value: segment.km,
+                    onchange: move |evt: FormEvent| {
+                        further_segments.write()[index].km = evt.parsed()?;
+                        Ok(())
+                    },
+                    min: 0,
+                },
+                label { for: "input-segment-{index}-begin", " {t(Key::SegmentBegin, lang)} " },
+                input { id: "input-segment-{index}-begin",
+                    r#type: "datetime-local",
+                    value: segment.begin.to_string(),
+                    onchange: move |evt: FormEvent| {
+                        further_segments.write()[index].begin = wall_clock(evt.parsed()?);
+                        Ok(())
+                    },
+                },
+                label { for: "input-segment-{index}-end", " {t(Key::SegmentEnd, lang)} " },
+                input { id: "input-segment-{index}-end",
+                    r#type: "datetime-local",
+                    value: segment.end.to_string(),
+                    onchange: move |evt: FormEvent| {
+                        further_segments.write()[index].end = wall_clock(evt.parsed()?);
+                        Ok(())
+                    },
+                },
+                " ",
+                button { onclick: move |_| { further_segments.write().remove(index); }, {t(Key::RemoveSegment, lang)} },
+                if let Some(reason) = segment.invalid_reason() {
+                    span { class: "unavailable", " {t(reason, lang)}" }
+                }
+            }
+        }
+        p {
+            button { onclick: add_clicked, {t(Key::AddSegment, lang)} },
+        }
+    }
+}
+
 #[component]
-fn ProviderSection(provider: Provider, input_data: Signal<TripInputData>) -> Element {
+fn ProviderSection(
+    provider: Provider,
+    input_data: Signal<TripInputData>,
+    further_segments: Signal<Vec<TripInputData>>,
+    /// Whether the trip distance is being looked up, the result would be of the old one.
+    computing_km: bool,
+    is_cheapest: bool,
+    /// Name of the provider chosen to compare the others to.
+    baseline: Signal<Option<String>>,
+    /// Difference of the total from the one of the baseline, if both are compared.
+    delta_czk: Option<f64>,
+    show_disabled: bool,
+    show_without_airport: bool,
+    show_without_packages: bool,
+    /// Whether to dump the state of the provider below its result.
+    show_debug: bool,
+    feedback: Signal<Vec<Feedback>>,
+    /// Manual reordering, `None` when the order is given by something else.
+    on_move: Option<EventHandler<Direction>>,
+) -> Element {
     let name = provider.name();
     debug!("ProviderSection for {name} rendering...");
+    let lang = use_lang();
+    let currency = use_currency();
 
     let enabled_changed = move |evt: FormEvent| {
         provider.enabled.set(evt.parsed()?);
         Ok(())
     };
 
-    // TODO(Matej): does this need a memo or something like that?
-    let result = provider.calculate(input_data);
+    // Only recalculate when the trip, this provider or show_disabled change, the memo subscribes to
+    // signals read inside it. Errors are formatted as anyhow::Error is not PartialEq.
+    let result =
+        use_memo(use_reactive((&provider, &show_disabled), move |(provider, show_disabled)| {
+            debug!("calculating {name}...");
+            provider
+                .shown_result(&input_data.read().itinerary(&further_segments.read()), show_disabled)
+                .map(|result| result.map_err(|e| format!("{e:#}")))
+        }));
+    let result = result();
+    let enabled = *provider.enabled.read();
+    let is_baseline = baseline.read().as_deref() == Some(name);
+    let delta = delta_czk.filter(|_| !is_baseline).map(|delta_czk| {
+        let baseline = baseline().unwrap_or_default();
+        let text = format!("{} vs {baseline}", currency.short(delta_czk));
+        if delta_czk.abs() < EPSILON_CZK {
+            ("delta", format!("±{} vs {baseline}", currency.short(0.0)))
+        } else if delta_czk > 0.0 {
+            ("delta pricier", format!("+{text}"))
+        } else {
+            ("delta cheaper", text)
+        }
+    });
+    let unavailable_reason = provider.unavailable_reason(*input_data.read());
+    let branding = provider.branding();
+    let without_packages = show_without_packages
+        .then(|| provider.total_without_packages(*input_data.read()))
+        .flatten();
+    let without_airport =
+        show_without_airport.then(|| provider.total_without_airport(*input_data.read())).flatten();
+    let segments = input_data.read().itinerary(&further_segments.read());
+    let trip_km: f64 = segments.iter().map(|segment| segment.effective().km).sum();
+    let trip_duration = segments
+        .iter()
+        .fold(SignedDuration::ZERO, |total, segment| total + elapsed(segment.begin, segment.end));
+    let unit_price = |czk: Option<f64>, unit: &str| {
+        czk.map(|czk| {
+            let (value, symbol) = convert(czk, currency);
+            format!("{value:.2} {symbol}/{unit}")
+        })
+    };
 
     rsx! {
         div {
             key: name,
-            class: "provider",
-            h3 {
+            class: match (is_cheapest, enabled) {
+                (true, _) => "provider cheapest",
+                (false, true) => "provider",
+                (false, false) => "provider disabled",
+            },
+            h3 { style: branding.header_style(),
+                if let Some(logo) = branding.logo {
+                    img { class: "provider-logo", src: logo, alt: "{name}" }
+                }
                 input { id: "provider-{name}-enabled",
                     r#type: "checkbox",
                     checked: provider.enabled,
                     onchange: enabled_changed,
                 }
                 label { for: "provider-{name}-enabled", " {name}" },
+                if is_cheapest {
+                    span { class: "badge", " {t(Key::Cheapest, lang)}" }
+                }
+                " ",
+                input { id: "provider-{name}-baseline",
+                    r#type: "radio",
+                    name: "baseline",
+                    checked: is_baseline,
+                    onchange: move |_| baseline.set(Some(name.to_string())),
+                }
+                label { for: "provider-{name}-baseline", " {t(Key::Baseline, lang)}" },
+                if let Some(on_move) = on_move {
+                    span { class: "reorder",
+                        button { title: t(Key::MoveLeft, lang), onclick: move |_| on_move(Direction::Up), "◀" }
+                        button { title: t(Key::MoveRight, lang), onclick: move |_| on_move(Direction::Down), "▶" }
+                    }
+                }
             },
-            match provider.kind {
-                ProviderKind::Bolt(_bolt) => rsx!("TODO Bolt"),
-                ProviderKind::Car4way(car4way) => rsx! { Car4wayInput { car4way } },
+            {provider.render_input(*input_data.read())}
+            match (unavailable_reason, result) {
+                _ if computing_km => rsx! {
+                    span { class: "result unavailable", "{t(Key::WaitingForDistance, lang)}" }
+                },
+                (_, None) => rsx! {},
+                (Some(reason), _) => rsx! { span { class: "result unavailable", "{reason}" } },
+                (None, Some(Ok(result))) => rsx! {
+                    VisualizedResult { result: result.clone(), budget_czk: input_data.read().budget_czk }
+                    if input_data.read().passengers > 1 {
+                        span { class: "note",
+                            " ({t(Key::PerPerson, lang)} {currency.short(result.per_person_czk(input_data.read().passengers))})"
+                        }
+                    }
+                    if is_baseline {
+                        span { class: "note", " ({t(Key::Baseline, lang)})" }
+                    }
+                    if let Some((class, delta)) = delta {
+                        span { class, " {delta}" }
+                    }
+                    for unit_price in [
+                        unit_price(result.per_km_czk(trip_km), "km"),
+                        unit_price(result.per_hour_czk(trip_duration), "h"),
+                    ].into_iter().flatten() {
+                        span { class: "note", " ({unit_price})" }
+                    }
+                    match without_packages {
+                        None => rsx! {},
+                        Some(Ok(total_czk)) => rsx! { span { class: "note", " ({t(Key::PurelyPerMinute, lang)} {currency.short(total_czk)})" } },
+                        Some(Err(e)) => rsx! { span { class: "unavailable", " ({t(Key::PurelyPerMinute, lang)}: {t(Key::Error, lang)} {e:#})" } },
+                    }
+                    match without_airport {
+                        None => rsx! {},
+                        Some(Ok(total_czk)) => rsx! { span { class: "note", " ({t(Key::WithoutAirport, lang)} {currency.short(total_czk)})" } },
+                        Some(Err(e)) => rsx! { span { class: "unavailable", " ({t(Key::WithoutAirport, lang)}: {t(Key::Error, lang)} {e:#})" } },
+                    }
+                    PriceBreakdown { result: result.clone() }
+                    InvoiceCompare { provider: name, result: result.clone() }
+                    FeedbackControls {
+                        provider: name,
+                        input_data: *input_data.read(),
+                        result,
+                        feedback,
+                    }
+                },
+                (None, Some(Err(e))) => rsx! {
+                    span { class: "result unavailable", "{t(Key::CalculationError, lang)}: {e:#}" }
+                },
+            }
+            if show_debug {
+                pre { class: "debug", "{provider:#?}" }
             }
-            VisualizedResult { result },
-            pre { "{provider:#?}" }
         }
     }
 }
 
 #[component]
-fn VisualizedResult(result: CalculationResult) -> Element {
-    let total_czk = result.total_czk();
-    let mut title = result.car_type;
-    for component in result.components {
-        title.push_str(&format!(", {} ({:.0} Kč)", component.name, component.czk));
+fn VisualizedResult(result: CalculationResult, budget_czk: Option<f64>) -> Element {
+    let lang = use_lang();
+    let currency = use_currency();
+    let total = currency.short(result.total_czk());
+    let over_budget_czk = budget_czk.map(|budget_czk| result.over_budget_czk(budget_czk));
+    let mut title = result.car_type.clone();
+    for (group, components) in result.grouped_components() {
+        title.push_str(&format!("\n{}:", group.label(lang)));
+        for component in components {
+            title.push_str(&format!(" {} ({}),", component.name, currency.short(component.czk)));
+        }
+        title.pop();
     }
 
     rsx! {
-        span { class: "result", title: title, "{total_czk:.0} Kč" },
+        span { class: "result", title: title, "{total}" },
+        match over_budget_czk {
+            None => rsx! {},
+            Some(None) => rsx! { span { class: "budget within-budget", " ✔" } },
+            Some(Some(overage)) => rsx! {
+                span { class: "budget over-budget", " ✘ {over_budget(&currency.short(overage), lang)}" }
+            },
+        }
+    }
+}
+
+/// One row per price component and the total, so that it is clear why a provider costs what it
+/// does.
+#[component]
+fn PriceBreakdown(result: CalculationResult) -> Element {
+    let lang = use_lang();
+    let currency = use_currency();
+
+    rsx! {
+        table { class: "breakdown",
+            caption { "{result.car_type}" }
+            for component in result.components.iter() {
+                tr {
+                    td { class: if component.description.is_some() { "described" },
+                        title: component.description.clone(),
+                        "{component.name}"
+                    }
+                    td { class: "czk", "{format_price(component.czk, currency)}" }
+                }
+            }
+            tr { class: "total",
+                th { {t(Key::Total, lang)} }
+                th { class: "czk", "{format_price(result.total_czk(), currency)}" }
+            }
+        }
+    }
+}
+
+/// Format `span` like `2 d 3 h` or `1 h 30 min`, to whole minutes. Days are of 24 hours, spans
+/// shorter than half a minute (also negative ones) are `0 min`.
+pub fn format_duration(span: Span) -> String {
+    let rounding = SpanRound::new().largest(Unit::Day).smallest(Unit::Minute).days_are_24_hours();
+    let span = match span.round(rounding) {
+        Ok(span) if span.is_positive() => span,
+        _ => return "0 min".to_string(),
+    };
+    let parts = [
+        (i64::from(span.get_days()), "d"),
+        (i64::from(span.get_hours()), "h"),
+        (span.get_minutes(), "min"),
+    ];
+    let parts: Vec<_> = parts
+        .into_iter()
+        .filter(|&(count, _)| count > 0)
+        .map(|(count, unit)| format!("{count} {unit}"))
+        .collect();
+    parts.join(" ")
+}
+
+/// Format `czk` in `currency` the Czech way: decimal comma and (non-breaking) spaces between
+/// thousands.
+pub fn format_price(czk: f64, currency: DisplayCurrency) -> String {
+    let (value, symbol) = convert(czk, currency);
+    let formatted = format!("{:.2}", value.abs());
+    let (whole, fraction) = formatted.split_once('.').expect("formatted with decimals");
+    let sign = if value < 0.0 && formatted != "0.00" { "-" } else { "" };
+    format!("{sign}{},{fraction}\u{a0}{symbol}", group_thousands(whole))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::civil::date;
+    use test_log::test;
+
+    #[test]
+    fn test_total_time() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let total_time = |end| TripInputData::from_times(10.0, begin, end).total_time();
+
+        assert_eq!(total_time(begin + 2.hours() + 10.minutes()), Ok("2 h 10 min".to_string()));
+        assert_eq!(total_time(begin), Ok("0 min".to_string()));
+        assert_eq!(total_time(begin - 1.minute()), Err(Key::EndBeforeBegin));
+
+        let round_trip = TripInputData {
+            round_trip: true,
+            ..TripInputData::from_times(10.0, begin, begin + 1.hour())
+        };
+        assert_eq!(round_trip.total_time(), Ok("1 h".to_string()), "time is not doubled");
+    }
+
+    #[test]
+    fn test_starting_after_dst_gap() {
+        // Clocks jump from 02:00 to 03:00 CEST, the trip begins at the first existing time.
+        let now: Zoned = "2025-03-30T01:58:00+01:00[Europe/Prague]".parse().unwrap();
+        let trip = TripInputData::starting_after(&now).unwrap();
+        assert_eq!(trip.begin, date(2025, 3, 30).at(3, 0, 0, 0));
+        assert_eq!(trip.end, date(2025, 3, 30).at(5, 10, 0, 0));
+        assert_eq!(elapsed(trip.begin, trip.end), SignedDuration::from_mins(130));
+
+        // Two real hours after 01:55 CET span the transition.
+        let now: Zoned = "2025-03-30T01:52:00+01:00[Europe/Prague]".parse().unwrap();
+        let trip = TripInputData::starting_after(&now).unwrap();
+        assert_eq!(trip.begin, date(2025, 3, 30).at(1, 55, 0, 0));
+        assert_eq!(trip.end, date(2025, 3, 30).at(5, 5, 0, 0));
+
+        // Whatever the zone of the browser, times are Prague ones.
+        let now: Zoned = "2025-06-03T08:03:00+00:00[UTC]".parse().unwrap();
+        assert_eq!(
+            TripInputData::starting_after(&now).unwrap().begin,
+            date(2025, 6, 3).at(10, 5, 0, 0)
+        );
+        assert_eq!(
+            wall_clock(date(2025, 3, 30).at(2, 30, 0, 0)),
+            date(2025, 3, 30).at(3, 30, 0, 0)
+        );
+        assert_eq!(
+            wall_clock(date(2025, 10, 26).at(2, 30, 0, 0)),
+            date(2025, 10, 26).at(2, 30, 0, 0)
+        );
+        // The repeated 02:30 is the first one, still in summer time.
+        assert_eq!(
+            elapsed(date(2025, 10, 26).at(2, 30, 0, 0), date(2025, 10, 26).at(3, 0, 0, 0)),
+            SignedDuration::from_mins(90)
+        );
+    }
+
+    #[test]
+    fn test_time_presets() {
+        let now: Zoned = "2025-06-03T10:02:00+02:00[Europe/Prague]".parse().unwrap();
+        let trip = TripInputData {
+            passengers: 2,
+            ..TripInputData::from_times(
+                5.0,
+                date(2025, 5, 1).at(8, 0, 0, 0),
+                date(2025, 5, 1).at(9, 0, 0, 0),
+            )
+        };
+
+        let hour = trip.starting_now_for(&now, 1.hour()).unwrap();
+        assert_eq!(
+            (hour.begin, hour.end),
+            (date(2025, 6, 3).at(10, 5, 0, 0), date(2025, 6, 3).at(11, 5, 0, 0))
+        );
+        assert_eq!((hour.km, hour.passengers), (5.0, 2), "the rest is kept");
+        let day = trip.starting_now_for(&now, 1.day()).unwrap();
+        assert_eq!(day.end, date(2025, 6, 4).at(10, 5, 0, 0));
+
+        let weekend = |now: &str| {
+            let trip = trip.weekend(&now.parse().unwrap()).unwrap();
+            (trip.begin, trip.end)
+        };
+        let this_weekend = (date(2025, 6, 6).at(16, 0, 0, 0), date(2025, 6, 9).at(10, 0, 0, 0));
+        let next_weekend = (date(2025, 6, 13).at(16, 0, 0, 0), date(2025, 6, 16).at(10, 0, 0, 0));
+        assert_eq!(weekend("2025-06-03T10:02:00+02:00[Europe/Prague]"), this_weekend);
+        assert_eq!(weekend("2025-06-06T15:59:00+02:00[Europe/Prague]"), this_weekend);
+        assert_eq!(weekend("2025-06-06T16:00:00+02:00[Europe/Prague]"), next_weekend);
+        assert_eq!(weekend("2025-06-07T12:00:00+02:00[Europe/Prague]"), next_weekend);
+        // Late Thursday in UTC is already Friday in Prague.
+        assert_eq!(weekend("2025-06-05T22:30:00+00:00[UTC]"), this_weekend);
+    }
+
+    #[test]
+    fn test_airport() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData {
+            airport_leave: true,
+            ..TripInputData::from_times(5.0, begin, begin + 1.hour())
+        };
+
+        let to_airport = input_data.airport(true);
+        assert_eq!((to_airport.airport_enter, to_airport.airport_leave), (true, false));
+        assert_eq!(to_airport.km, 17.0);
+        assert_eq!((to_airport.begin, to_airport.end), (input_data.begin, input_data.end));
+        let from_airport = input_data.airport(false);
+        assert_eq!((from_airport.airport_enter, from_airport.airport_leave), (false, true));
+    }
+
+    #[test]
+    fn test_with_km_step() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(5.0, begin, begin + 1.hour());
+
+        assert_eq!(input_data.with_km_step(KM_STEPS[1]).km, 15.0);
+        assert_eq!(input_data.with_km_step(-KM_STEPS[0]).km, 4.0);
+        assert_eq!(input_data.with_km_step(-KM_STEPS[1]).km, 0.0, "clamped at zero");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(45.minutes()), "45 min");
+        assert_eq!(format_duration(90.minutes()), "1 h 30 min");
+        assert_eq!(format_duration(2.hours()), "2 h");
+        assert_eq!(format_duration(2.days().hours(3)), "2 d 3 h");
+        assert_eq!(format_duration(1.day().minutes(5)), "1 d 5 min");
+        assert_eq!(format_duration(50.hours()), "2 d 2 h");
+        assert_eq!(format_duration(20.seconds()), "0 min");
+        assert_eq!(format_duration(Span::new()), "0 min");
+        assert_eq!(format_duration(-3.hours()), "0 min");
+    }
+
+    #[test]
+    fn test_format_price() {
+        let format_czk = |czk| format_price(czk, DisplayCurrency::default());
+        assert_eq!(format_czk(0.0), "0,00\u{a0}Kč");
+        assert_eq!(format_czk(47.449), "47,45\u{a0}Kč");
+        assert_eq!(format_czk(999.999), "1\u{a0}000,00\u{a0}Kč");
+        assert_eq!(format_czk(1234567.5), "1\u{a0}234\u{a0}567,50\u{a0}Kč");
+        assert_eq!(format_czk(-1099.0), "-1\u{a0}099,00\u{a0}Kč");
+        assert_eq!(format_czk(-0.001), "0,00\u{a0}Kč");
+
+        let eur = DisplayCurrency { currency: currency::Currency::Eur, czk_per_eur: 25.0 };
+        assert_eq!(format_price(250.0, eur), "10,00\u{a0}€");
+        assert_eq!(format_price(277.47, eur), "11,10\u{a0}€");
+        assert_eq!(format_price(25_000.0, eur), "1\u{a0}000,00\u{a0}€");
+        assert_eq!(format_price(-47.45, eur), "-1,90\u{a0}€");
     }
 }
@@ -1,78 +1,1377 @@
 use crate::{
     TripInputData,
-    provider::{bolt::Bolt, car4way::Car4way},
+    currency::format_czk,
+    i18n::{Lang, t},
 };
-use dioxus::signals::{Readable, Signal};
-use std::cmp::Ordering;
+use anyhow::{Context, Result, bail};
+use dioxus::prelude::*;
+use jiff::{
+    SignedDuration, Zoned,
+    civil::{DateTime, Time},
+    tz::TimeZone,
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{any::Any, cmp::Ordering, fmt, rc::Rc, sync::LazyLock};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+use tracing::error;
 
+pub mod anytime;
+pub mod bike;
 pub mod bolt;
 pub mod car4way;
+pub mod owncar;
+pub mod pid;
+pub mod taxi;
+pub mod uber;
+pub mod walk;
 
-#[derive(Debug, Clone, PartialEq)]
+/// A provider implementation, [`Provider`] holds its state in a signal so that its inputs can
+/// change it. Only [`ProviderImpl::name()`], [`ProviderImpl::id()`] and
+/// [`ProviderImpl::calculate()`] are mandatory.
+pub trait ProviderImpl: fmt::Debug + 'static {
+    fn name(&self) -> &'static str;
+
+    /// Stable identifier independent of [`ProviderImpl::name()`], e.g. for URLs.
+    fn id(&self) -> &'static str;
+
+    /// Price of the trip before [`ProviderImpl::invoice_rounding()`].
+    fn calculate(&self, input_data: TripInputData) -> Result<CalculationResult>;
+
+    /// User-facing explanation why the provider cannot be used for the trip.
+    fn unavailable_reason(&self, _input_data: TripInputData) -> Option<String> {
+        None
+    }
+
+    fn branding(&self) -> Branding {
+        Branding::default()
+    }
+
+    /// Whether [`TripInputData::packages`] makes any difference.
+    fn supports_packages(&self) -> bool {
+        false
+    }
+
+    fn invoice_rounding(&self) -> InvoiceRounding {
+        InvoiceRounding::WholeCrown
+    }
+
+    /// Inputs of the provider-specific settings, if any. `input_data` is the
+    /// [effective](TripInputData::effective) trip, like in [`ProviderImpl::calculate()`].
+    fn render_input(_provider: Signal<Self>, _input_data: TripInputData) -> Element
+    where
+        Self: Sized,
+    {
+        rsx! {}
+    }
+}
+
+/// Object-safe access to a provider in a signal, see [`ProviderImpl`].
+trait ProviderState: fmt::Debug {
+    fn with(&self, f: &mut dyn FnMut(&dyn ProviderImpl));
+    fn render_input(&self, input_data: TripInputData) -> Element;
+    fn as_any(&self) -> &dyn Any;
+    /// Whether `other` is the very same signal.
+    fn same_as(&self, other: &dyn ProviderState) -> bool;
+    fn settings(&self) -> Result<serde_json::Value>;
+    /// Parse `settings`, returning what sets them.
+    fn prepare_settings(&self, settings: serde_json::Value) -> Result<Box<dyn FnOnce()>>;
+}
+
+impl<T: ProviderImpl + Serialize + DeserializeOwned> ProviderState for Signal<T> {
+    fn with(&self, f: &mut dyn FnMut(&dyn ProviderImpl)) {
+        f(&*self.read())
+    }
+
+    fn render_input(&self, input_data: TripInputData) -> Element {
+        T::render_input(*self, input_data)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn same_as(&self, other: &dyn ProviderState) -> bool {
+        other.as_any().downcast_ref::<Self>() == Some(self)
+    }
+
+    fn settings(&self) -> Result<serde_json::Value> {
+        Ok(serde_json::to_value(&*self.read())?)
+    }
+
+    fn prepare_settings(&self, settings: serde_json::Value) -> Result<Box<dyn FnOnce()>> {
+        let mut signal = *self;
+        let settings: T = serde_json::from_value(settings)?;
+        Ok(Box::new(move || signal.set(settings)))
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Provider {
     pub enabled: Signal<bool>,
-    pub kind: ProviderKind,
+    state: Rc<dyn ProviderState>,
+}
+
+impl PartialEq for Provider {
+    fn eq(&self, other: &Self) -> bool {
+        self.enabled == other.enabled && self.state.same_as(&*other.state)
+    }
 }
 
 impl Provider {
-    pub fn new(enabled: Signal<bool>, kind: ProviderKind) -> Self {
-        Self { enabled, kind }
+    pub fn new<T>(enabled: Signal<bool>, provider: Signal<T>) -> Self
+    where
+        T: ProviderImpl + Serialize + DeserializeOwned,
+    {
+        Self { enabled, state: Rc::new(provider) }
+    }
+
+    /// Call `f` with the current state of the provider, subscribing to it.
+    fn with<R>(&self, f: impl FnOnce(&dyn ProviderImpl) -> R) -> R {
+        let mut f = Some(f);
+        let mut output = None;
+        self.state.with(&mut |provider| output = f.take().map(|f| f(provider)));
+        output.expect("ProviderState::with() calls f")
     }
 
     pub fn name(&self) -> &'static str {
-        match &self.kind {
-            ProviderKind::Bolt(bolt) => bolt.read().name(),
-            ProviderKind::Car4way(car4way) => car4way.read().name(),
+        self.with(|provider| provider.name())
+    }
+
+    /// See [`ProviderImpl::id()`].
+    pub fn id(&self) -> &'static str {
+        self.with(|provider| provider.id())
+    }
+
+    pub fn branding(&self) -> Branding {
+        self.with(|provider| provider.branding())
+    }
+
+    /// Whether the provider makes sense for the trip at all; inapplicable ones are not compared.
+    pub fn is_applicable(&self, input_data: TripInputData) -> bool {
+        self.unavailable_reason(input_data).is_none()
+    }
+
+    /// User-facing explanation why the provider cannot be used for the trip.
+    pub fn unavailable_reason(&self, input_data: TripInputData) -> Option<String> {
+        let input_data = input_data.effective();
+        if let Some(reason) = input_data.invalid_reason() {
+            return Some(t(reason, Lang::default()).into());
+        }
+        self.with(|provider| provider.unavailable_reason(input_data))
+    }
+
+    /// Calculate the invoiced price, i.e. including [`InvoiceRounding`], of the
+    /// [effective](TripInputData::effective) trip, less its [`Discount`].
+    pub fn calculate(&self, input_data: TripInputData) -> Result<CalculationResult> {
+        let mut result = self.calculate_invoiced(input_data)?;
+        input_data.discount.apply(&mut result);
+        Ok(result)
+    }
+
+    /// [`Provider::calculate()`] without the discount. Invalid trips are not calculated at all,
+    /// their [reason](TripInputData::invalid_reason) is the error.
+    fn calculate_invoiced(&self, input_data: TripInputData) -> Result<CalculationResult> {
+        let input_data = input_data.effective();
+        if let Some(reason) = input_data.invalid_reason() {
+            bail!(t(reason, Lang::default()));
+        }
+        let mut result = self.with(|provider| provider.calculate(input_data))?;
+        self.invoice_rounding().apply(&mut result);
+        result.provider = Some(self.name());
+        Ok(result)
+    }
+
+    /// Sum of separately [calculated](Provider::calculate()) `segments` of a trip with stops,
+    /// i.e. without packages spanning more of them. Components are prefixed by the segment number,
+    /// the discount of the first segment applies once to the sum.
+    pub fn calculate_segments(&self, segments: &[TripInputData]) -> Result<CalculationResult> {
+        let [first, rest @ ..] = segments else {
+            bail!("no trip segments");
+        };
+        if rest.is_empty() {
+            if let Some(reason) = self.unavailable_reason(*first) {
+                bail!(reason);
+            }
+            return self.calculate(*first);
+        }
+
+        let mut car_types: Vec<String> = vec![];
+        let mut total = CalculationResult::new("");
+        total.provider = Some(self.name());
+        for (number, segment) in (1..).zip(segments) {
+            if let Some(reason) = self.unavailable_reason(*segment) {
+                bail!("{number}. úsek: {reason}");
+            }
+            let result =
+                self.calculate_invoiced(*segment).with_context(|| format!("{number}. úsek"))?;
+            if !car_types.contains(&result.car_type) {
+                car_types.push(result.car_type);
+            }
+            if let Some(package) = result.package {
+                total.package = Some(match total.package {
+                    Some(packages) => format!("{packages}, {package}"),
+                    None => package,
+                });
+            }
+            for component in result.components {
+                let name = format!("{number}. úsek: {}", component.name);
+                total.components.push(PriceComponent { name, ..component });
+            }
+        }
+        total.car_type = car_types.join(", ");
+        first.discount.apply(&mut total);
+        Ok(total)
+    }
+
+    /// Result to display: disabled providers are only calculated if `show_disabled` is set. Unlike
+    /// [`compare_all()`], this does not exclude them from being shown.
+    pub fn shown_result(
+        &self,
+        segments: &[TripInputData],
+        show_disabled: bool,
+    ) -> Option<Result<CalculationResult>> {
+        (*self.enabled.read() || show_disabled).then(|| self.calculate_segments(segments))
+    }
+
+    pub fn supports_packages(&self) -> bool {
+        self.with(|provider| provider.supports_packages())
+    }
+
+    /// Invoiced total of the trip using just the base (per-minute and per-km) prices, `None` if
+    /// the provider has no packages anyway.
+    pub fn total_without_packages(&self, input_data: TripInputData) -> Option<Result<f64>> {
+        self.supports_packages().then(|| {
+            let result = self.calculate(TripInputData { packages: false, ..input_data })?;
+            Ok(result.total_czk())
+        })
+    }
+
+    /// Invoiced total of the same trip without its airport fees, `None` if it has none.
+    pub fn total_without_airport(&self, input_data: TripInputData) -> Option<Result<f64>> {
+        input_data.has_airport().then(|| {
+            let result = self.calculate(input_data.without_airport())?;
+            Ok(result.total_czk())
+        })
+    }
+
+    pub fn invoice_rounding(&self) -> InvoiceRounding {
+        self.with(|provider| provider.invoice_rounding())
+    }
+
+    /// See [`ProviderImpl::render_input()`].
+    pub fn render_input(&self, input_data: TripInputData) -> Element {
+        self.state.render_input(input_data.effective())
+    }
+
+    /// Provider-specific settings (not [`Provider::enabled`]) in JSON.
+    pub fn settings(&self) -> Result<serde_json::Value> {
+        self.state.settings()
+    }
+
+    /// Replace the settings by ones from [`Provider::settings()`].
+    pub fn set_settings(&self, settings: serde_json::Value) -> Result<()> {
+        self.prepare_settings(settings).map(|set| set())
+    }
+
+    /// Validate `settings` for [`Provider::set_settings()`] without setting them yet, so that
+    /// several providers can be changed all or nothing.
+    pub fn prepare_settings(&self, settings: serde_json::Value) -> Result<Box<dyn FnOnce()>> {
+        self.state.prepare_settings(settings).with_context(|| format!("settings of {}", self.id()))
+    }
+}
+
+/// Which way to round billed quantities (minutes, kilometers) that are not whole.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumIter,
+    Display,
+    EnumString,
+    Serialize,
+    Deserialize,
+)]
+pub enum Rounding {
+    /// Round up, like the providers bill started units.
+    #[default]
+    Pessimistic,
+    /// Round down, in the user's favor.
+    Optimistic,
+}
+
+impl Rounding {
+    pub fn label(&self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (Rounding::Pessimistic, Lang::Cs) => "nahoru (jako poskytovatel)",
+            (Rounding::Pessimistic, Lang::En) => "up (like the provider)",
+            (Rounding::Optimistic, Lang::Cs) => "dolů (ve váš prospěch)",
+            (Rounding::Optimistic, Lang::En) => "down (in your favor)",
+        }
+    }
+
+    pub fn round(&self, value: f64) -> f64 {
+        match self {
+            Rounding::Pessimistic => value.ceil(),
+            Rounding::Optimistic => value.floor(),
+        }
+    }
+
+    /// Whole minutes to bill for `duration`.
+    pub fn minutes(&self, duration: SignedDuration) -> i64 {
+        self.round(duration.as_secs_f64() / 60.0) as i64
+    }
+}
+
+/// Daily interval of wall-clock times from `start` (inclusive) to `end` (exclusive), wrapping over
+/// midnight if `end` is not after `start`, like night tariffs do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeWindow {
+    pub start: Time,
+    pub end: Time,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, time: Time) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            self.start <= time || time < self.end
+        }
+    }
+}
+
+/// Trip times are wall-clock ones in the zone all providers operate in, whatever the zone of the
+/// browser is.
+pub static PRAGUE: LazyLock<TimeZone> =
+    LazyLock::new(|| TimeZone::get("Europe/Prague").expect("time zone database is bundled"));
+
+/// Current time in [`PRAGUE`].
+pub fn now_in_prague() -> Zoned {
+    Zoned::now().with_time_zone(PRAGUE.clone())
+}
+
+/// `datetime` as it is on Prague clocks: a time skipped by a DST transition is moved forward by
+/// the length of the gap, like a clock would show it. Applied to every entered time.
+///
+/// A time repeated by a transition back stays as it is and, like everywhere in [`elapsed()`] and
+/// [`after()`], means its first occurrence (still in summer time).
+pub fn wall_clock(datetime: DateTime) -> DateTime {
+    PRAGUE.to_zoned(datetime).map_or(datetime, |zoned| zoned.datetime())
+}
+
+/// Real time elapsed between wall-clock `begin` and `end`, which differs from their naive
+/// difference by an hour when a DST transition is in between.
+pub fn elapsed(begin: DateTime, end: DateTime) -> SignedDuration {
+    match (PRAGUE.to_zoned(begin), PRAGUE.to_zoned(end)) {
+        (Ok(begin), Ok(end)) => end.duration_since(&begin),
+        _ => end.duration_since(begin),
+    }
+}
+
+/// Wall-clock time when real `duration` elapses after wall-clock `begin`, the inverse of
+/// [`elapsed()`].
+pub fn after(begin: DateTime, duration: SignedDuration) -> DateTime {
+    match PRAGUE.to_zoned(begin).and_then(|begin| begin.checked_add(duration)) {
+        Ok(end) => end.datetime(),
+        Err(_) => begin + duration,
+    }
+}
+
+/// How a provider rounds the final amount on its invoices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvoiceRounding {
+    /// To whole hundredths of a crown.
+    Haler,
+    /// To whole crowns, half up.
+    WholeCrown,
+}
+
+impl InvoiceRounding {
+    fn round(&self, czk: f64) -> f64 {
+        match self {
+            InvoiceRounding::Haler => (czk * 100.0).round() / 100.0,
+            InvoiceRounding::WholeCrown => czk.round(),
+        }
+    }
+
+    /// Add a component rounding the total of `result` the way the provider does.
+    pub fn apply(&self, result: &mut CalculationResult) {
+        let total_czk = result.total_czk();
+        let difference = self.round(total_czk) - total_czk;
+        if difference.abs() > 1e-9 {
+            result.add_component(difference, "zaokrouhlení");
+        }
+    }
+}
+
+/// Where the car is returned, relative to the provider's zone.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumIter,
+    Display,
+    EnumString,
+    Serialize,
+    Deserialize,
+)]
+pub enum ReturnLocation {
+    /// At any station or spot in the zone, free of charge.
+    #[default]
+    InZone,
+    OutOfZone,
+}
+
+impl ReturnLocation {
+    pub fn label(&self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (ReturnLocation::InZone, Lang::Cs) => "v zóně",
+            (ReturnLocation::InZone, Lang::En) => "in the zone",
+            (ReturnLocation::OutOfZone, Lang::Cs) => "mimo zónu",
+            (ReturnLocation::OutOfZone, Lang::En) => "out of the zone",
         }
     }
 
-    pub fn calculate(&self, input_data: Signal<TripInputData>) -> CalculationResult {
-        match &self.kind {
-            ProviderKind::Bolt(_bolt) => {
-                CalculationResult { car_type: "TODO".into(), components: vec![] }
-            },
-            ProviderKind::Car4way(car4way) => car4way.read().calculate(*input_data.read()),
+    /// Bill `out_of_zone_czk` if the car is returned out of the zone.
+    pub fn apply_fee(&self, out_of_zone_czk: f64, result: &mut CalculationResult) {
+        if *self == ReturnLocation::OutOfZone {
+            result.add_component(out_of_zone_czk, "vrácení mimo zónu");
         }
     }
 }
 
+/// Optional per-day insurance (deductible reduction) add-on.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Insurance {
+    pub enabled: bool,
+    pub per_day_czk: f64,
+}
+
+impl Insurance {
+    pub fn new(per_day_czk: f64) -> Self {
+        Self { enabled: false, per_day_czk }
+    }
+
+    /// Bill every started rental day, if enabled.
+    pub fn apply(&self, input_data: TripInputData, result: &mut CalculationResult) {
+        let minutes = elapsed(input_data.begin, input_data.end).as_mins();
+        let Ok(minutes) = u64::try_from(minutes) else {
+            return;
+        };
+        if !self.enabled || minutes == 0 {
+            return;
+        }
+
+        let days = minutes.div_ceil(MINUTES_PER_DAY);
+        let name = match days {
+            1 => "pojištění 1 den".to_string(),
+            2..=4 => format!("pojištění {days} dny"),
+            _ => format!("pojištění {days} dní"),
+        };
+        result.add_component(days as f64 * self.per_day_czk, name);
+    }
+}
+
+const MINUTES_PER_DAY: u64 = 24 * 60;
+
+/// Promo credit the user subtracts from the price of the trip.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Discount {
+    pub kind: DiscountKind,
+    /// Percent or CZK, depending on [`Discount::kind`].
+    pub amount: f64,
+}
+
+impl Discount {
+    /// Subtract the discount as the last component: percents of the subtotal, or a fixed amount
+    /// bringing the total down to zero at most.
+    pub fn apply(&self, result: &mut CalculationResult) {
+        let subtotal_czk = result.total_czk();
+        let czk = match self.kind {
+            DiscountKind::None => return,
+            DiscountKind::Percent => subtotal_czk * self.amount.clamp(0.0, 100.0) / 100.0,
+            DiscountKind::Czk => self.amount.min(subtotal_czk),
+        };
+        if czk > 0.0 {
+            result.add_component(-czk, "sleva");
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumIter,
+    Display,
+    EnumString,
+    Serialize,
+    Deserialize,
+)]
+pub enum DiscountKind {
+    #[default]
+    None,
+    Percent,
+    Czk,
+}
+
+impl DiscountKind {
+    pub fn label(&self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (DiscountKind::None, Lang::Cs) => "žádná",
+            (DiscountKind::None, Lang::En) => "none",
+            (DiscountKind::Percent, _) => "%",
+            (DiscountKind::Czk, Lang::Cs) => "Kč",
+            (DiscountKind::Czk, Lang::En) => "CZK",
+        }
+    }
+}
+
+/// Visual identity of a provider used to style its section.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Branding {
+    pub logo: Option<Asset>,
+    /// CSS color of the section header.
+    pub color: Option<&'static str>,
+}
+
+impl Branding {
+    const DEFAULT_COLOR: &str = "gray";
+
+    /// Inline CSS for the section header.
+    pub fn header_style(&self) -> String {
+        format!("border-bottom: 3px solid {};", self.color.unwrap_or(Self::DEFAULT_COLOR))
+    }
+}
+
+/// Direction to move a provider in the user-defined order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+/// Move the provider at `index` one place in `direction`, no-op at the edges. The user-defined
+/// order is independent of the price ranking of [`compare_all()`].
+pub fn move_provider(providers: &mut [Provider], index: usize, direction: Direction) {
+    let other = match direction {
+        Direction::Up => index.checked_sub(1),
+        Direction::Down => Some(index + 1).filter(|&other| other < providers.len()),
+    };
+    if let Some(other) = other {
+        providers.swap(index, other);
+    }
+}
+
+/// Results of all enabled and applicable providers for the trip made of `segments` (see
+/// [`Provider::calculate_segments()`]), cheapest first. Providers failing to calculate are left out.
+///
+/// This is the single source of truth for anything comparing providers against each other. The
+/// ranking is by invoiced totals, i.e. already rounded by each provider's [`InvoiceRounding`], so
+/// that the cheapest pick matches what the user actually pays.
+pub fn compare_all(providers: &[Provider], segments: &[TripInputData]) -> Vec<CalculationResult> {
+    let mut results: Vec<_> = providers
+        .iter()
+        .filter(|provider| {
+            *provider.enabled.read()
+                && segments.iter().all(|segment| provider.is_applicable(*segment))
+        })
+        .filter_map(|provider| {
+            let result = provider.calculate_segments(segments);
+            result.inspect_err(|e| error!("calculating {}: {e:#}", provider.name())).ok()
+        })
+        .collect();
+    // Stable sort, ties keep the order of providers.
+    results.sort_by(CalculationResult::cmp_price);
+    results
+}
+
+/// Prices closer than this (half a haler) are considered equal.
+pub const EPSILON_CZK: f64 = 0.005;
+
+/// Names of the cheapest providers in `ranking` from [`compare_all()`], more of them on a tie.
+pub fn cheapest_names(ranking: &[CalculationResult]) -> Vec<&'static str> {
+    let Some(cheapest) = ranking.first() else {
+        return vec![];
+    };
+    ranking
+        .iter()
+        .take_while(|result| result.total_czk() - cheapest.total_czk() < EPSILON_CZK)
+        .map(CalculationResult::provider_name)
+        .collect()
+}
+
+/// How much more `name` costs than `baseline`, both looked up in `ranking` from [`compare_all()`]
+/// so that only enabled providers are compared. `None` if either is missing there.
+pub fn baseline_delta_czk(
+    ranking: &[CalculationResult],
+    baseline: &str,
+    name: &str,
+) -> Option<f64> {
+    let total_czk = |wanted: &str| {
+        ranking.iter().find(|result| result.provider_name() == wanted).map(|r| r.total_czk())
+    };
+    Some(total_czk(name)? - total_czk(baseline)?)
+}
+
+/// `providers` in the order of `ranking` from [`compare_all()`], the ones missing there (disabled,
+/// inapplicable or failing) last in their original order.
+pub fn sort_by_ranking(providers: &[Provider], ranking: &[CalculationResult]) -> Vec<Provider> {
+    let mut sorted = providers.to_vec();
+    sorted.sort_by_key(|provider| {
+        let position = ranking.iter().position(|result| result.provider_name() == provider.name());
+        position.unwrap_or(ranking.len())
+    });
+    sorted
+}
+
+/// The cheapest provider compared to the next one.
 #[derive(Debug, Clone, PartialEq)]
-pub enum ProviderKind {
-    Bolt(Signal<Bolt>),
-    Car4way(Signal<Car4way>),
+pub struct CheapestSummary {
+    pub winner: String,
+    pub runner_up: Option<String>,
+    /// How much more the runner-up costs.
+    pub margin_czk: f64,
+    /// Package of the winner, which usually is why it wins.
+    pub package: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl CheapestSummary {
+    /// Summarize results sorted by [`compare_all()`].
+    pub fn new(results: &[CalculationResult]) -> Option<Self> {
+        let winning_result = results.first()?;
+        let runner_up = results.get(1);
+        let margin_czk =
+            runner_up.map_or(0.0, |result| result.total_czk() - winning_result.total_czk());
+        Some(Self {
+            winner: winning_result.provider_name().to_string(),
+            runner_up: runner_up.map(|result| result.provider_name().to_string()),
+            margin_czk,
+            package: winning_result.package.clone(),
+        })
+    }
+
+    /// The summary as a sentence in `lang`.
+    pub fn text(&self, lang: Lang) -> String {
+        let margin = format_czk(self.margin_czk);
+        let mut text = match lang {
+            Lang::Cs => format!("nejlevnější je {}", self.winner),
+            Lang::En => format!("{} is the cheapest", self.winner),
+        };
+        if let Some(runner_up) = &self.runner_up {
+            text.push_str(&match lang {
+                Lang::Cs => format!(", o {margin} před {runner_up}"),
+                Lang::En => format!(", {margin} ahead of {runner_up}"),
+            });
+        }
+        if let Some(package) = &self.package {
+            text.push_str(&match lang {
+                Lang::Cs => format!(" (díky balíčku {package})"),
+                Lang::En => format!(" (thanks to the {package} package)"),
+            });
+        }
+        text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct CalculationResult {
     pub car_type: String,
     pub components: Vec<PriceComponent>,
+    /// Name of the package used, if any.
+    pub package: Option<String>,
+    /// [Name](Provider::name) of the provider, filled in by [`Provider::calculate()`]. Not
+    /// considered when ordering results, only the total is.
+    pub provider: Option<&'static str>,
 }
 
 impl CalculationResult {
+    pub fn new(car_type: impl Into<String>) -> Self {
+        Self { car_type: car_type.into(), components: vec![], package: None, provider: None }
+    }
+
+    /// [`CalculationResult::provider`], empty if not calculated through a [`Provider`].
+    pub fn provider_name(&self) -> &'static str {
+        self.provider.unwrap_or_default()
+    }
+
+    /// Construct a [`PriceComponent`] and store it in one go.
+    pub fn add_component(&mut self, czk: f64, name: impl Into<String>) {
+        self.components.push(PriceComponent { czk, name: name.into(), description: None });
+    }
+
+    /// Like [`CalculationResult::add_component()`], explaining how the price was arrived at.
+    pub fn add_described_component(
+        &mut self,
+        czk: f64,
+        name: impl Into<String>,
+        description: impl Into<String>,
+    ) {
+        let description = Some(description.into());
+        self.components.push(PriceComponent { czk, name: name.into(), description });
+    }
+
     pub fn total_czk(&self) -> f64 {
         self.components.iter().map(|c| c.czk).sum()
     }
-}
 
-#[expect(clippy::non_canonical_partial_ord_impl)]
-impl PartialOrd for CalculationResult {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.total_czk().partial_cmp(&other.total_czk())
+    /// Order by [`CalculationResult::total_czk()`], cheaper first. Not an [`Ord`] impl because
+    /// differing results of the same price are not equal.
+    pub fn cmp_price(&self, other: &Self) -> Ordering {
+        self.total_czk().partial_cmp(&other.total_czk()).expect("our floats compare")
+    }
+
+    /// Add a component bringing the total up to `min_total_czk` if it is below. Call it after all
+    /// other components, discounts included.
+    pub fn top_up_to(&mut self, min_total_czk: f64) {
+        let missing_czk = min_total_czk - self.total_czk();
+        if missing_czk > 0.0 {
+            self.add_component(missing_czk, "doplatek do minima");
+        }
+    }
+
+    /// Part of the total paid by each of `passengers` (at least one) when they split it evenly.
+    pub fn per_person_czk(&self, passengers: u32) -> f64 {
+        self.total_czk() / f64::from(passengers.max(1))
+    }
+
+    /// Total per kilometer of the trip, `None` if it has no distance.
+    pub fn per_km_czk(&self, km: f64) -> Option<f64> {
+        (km > 0.0).then(|| self.total_czk() / km)
+    }
+
+    /// Total per hour of the trip lasting `duration`, `None` if it takes no time.
+    pub fn per_hour_czk(&self, duration: SignedDuration) -> Option<f64> {
+        let hours = duration.as_secs_f64() / 3600.0;
+        (hours > 0.0).then(|| self.total_czk() / hours)
+    }
+
+    /// By how much the total exceeds `budget_czk`, `None` if it fits (including exactly).
+    pub fn over_budget_czk(&self, budget_czk: f64) -> Option<f64> {
+        let overage = self.total_czk() - budget_czk;
+        (overage > 0.0).then_some(overage)
     }
-}
 
-impl Ord for CalculationResult {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).expect("our floats compare")
+    /// Components by their [`ComponentGroup`], in the order of groups, omitting empty groups.
+    pub fn grouped_components(&self) -> Vec<(ComponentGroup, Vec<&PriceComponent>)> {
+        ComponentGroup::iter()
+            .map(|group| {
+                let components: Vec<_> =
+                    self.components.iter().filter(|c| c.group() == group).collect();
+                (group, components)
+            })
+            .filter(|(_, components)| !components.is_empty())
+            .collect()
     }
 }
 
 #[must_use]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct PriceComponent {
     pub czk: f64,
     pub name: String,
+    /// E.g. the arithmetic behind the price, shown as a tooltip.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl PriceComponent {
+    pub fn group(&self) -> ComponentGroup {
+        if self.czk > 0.0 {
+            ComponentGroup::Fee
+        } else if self.czk < 0.0 {
+            ComponentGroup::Discount
+        } else {
+            ComponentGroup::Informational
+        }
+    }
 }
 
 // We use floats that compare OK.
 impl Eq for PriceComponent {}
+
+/// Kind of a price component given by its sign.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum ComponentGroup {
+    Fee,
+    Discount,
+    Informational,
+}
+
+impl ComponentGroup {
+    pub fn label(&self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (ComponentGroup::Fee, Lang::Cs) => "poplatky",
+            (ComponentGroup::Fee, Lang::En) => "fees",
+            (ComponentGroup::Discount, Lang::Cs) => "slevy",
+            (ComponentGroup::Discount, Lang::En) => "discounts",
+            (ComponentGroup::Informational, Lang::Cs) => "informativní",
+            (ComponentGroup::Informational, Lang::En) => "informational",
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::provider::{bolt::Bolt, car4way::Car4way, owncar::OwnCar, taxi::Taxi, walk::Walk};
+    use jiff::{ToSpan, civil::date};
+    use test_log::test;
+
+    /// Signals need a Dioxus runtime, run `f` within one of an empty app.
+    pub fn with_signals<R>(f: impl FnOnce() -> R) -> R {
+        let mut dom = VirtualDom::new(VNode::empty);
+        dom.rebuild_in_place();
+        dom.in_runtime(|| ScopeId::ROOT.in_runtime(f))
+    }
+
+    #[test]
+    fn test_compare_all() {
+        with_signals(|| {
+            let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+            let input_data = TripInputData::from_times(1.0, begin, begin + 15.minutes());
+
+            let car4way = Provider::new(Signal::new(true), Signal::new(Car4way::default()));
+            let walk = Provider::new(Signal::new(true), Signal::new(Walk::default()));
+            let disabled_walk = Provider::new(Signal::new(false), Signal::new(Walk::default()));
+
+            let results = compare_all(&[car4way, disabled_walk, walk], &[input_data]);
+            let names: Vec<_> = results.iter().map(CalculationResult::provider_name).collect();
+            assert_eq!(names, ["pěšky/na kole", "car4way"]);
+            assert!(results[0].total_czk() <= results[1].total_czk());
+        })
+    }
+
+    #[test]
+    fn test_total_without_airport() {
+        with_signals(|| {
+            let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+            let input_data = TripInputData {
+                airport_enter: true,
+                airport_leave: true,
+                ..TripInputData::from_times(20.0, begin, begin + 40.minutes())
+            };
+            let car4way = Provider::new(Signal::new(true), Signal::new(Car4way::default()));
+
+            let with_airport = car4way.calculate(input_data).unwrap().total_czk();
+            let without_airport = car4way.total_without_airport(input_data).unwrap().unwrap();
+            // Both fees of the Basic tariff.
+            assert_eq!(with_airport - without_airport, 199.0 + 199.0);
+
+            assert!(car4way.total_without_airport(input_data.without_airport()).is_none());
+        })
+    }
+
+    #[test]
+    fn test_round_trip_doubles_km() {
+        with_signals(|| {
+            let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+            let one_way = TripInputData::from_times(2.0, begin, begin + 30.minutes());
+            let round_trip = TripInputData { round_trip: true, ..one_way };
+            let there_and_back = TripInputData { km: 4.0, ..one_way };
+
+            let own_car = Provider::new(Signal::new(true), Signal::new(OwnCar::default()));
+            assert_eq!(
+                own_car.calculate(round_trip).unwrap(),
+                own_car.calculate(there_and_back).unwrap()
+            );
+
+            // 2 km walk is fine, 4 km no longer.
+            let walk = Provider::new(Signal::new(true), Signal::new(Walk::default()));
+            assert!(walk.is_applicable(one_way));
+            assert!(!walk.is_applicable(round_trip));
+
+            // Driving there and back takes twice as long.
+            let taxi = Provider::new(Signal::new(true), Signal::new(Taxi::default()));
+            let round_trip = TripInputData { driving_minutes: Some(10), ..round_trip };
+            assert_eq!(
+                taxi.calculate(round_trip).unwrap(),
+                taxi.calculate(TripInputData { driving_minutes: Some(20), ..there_and_back })
+                    .unwrap()
+            );
+        })
+    }
+
+    #[test]
+    fn test_cheapest_names() {
+        let result = |provider, czk| {
+            let mut result = CalculationResult::new("auto");
+            result.add_component(czk, "jízda");
+            CalculationResult { provider: Some(provider), ..result }
+        };
+        let ranking =
+            [result("car4way", 100.0), result("Bolt", 100.001), result("vlastní auto", 101.0)];
+        assert_eq!(cheapest_names(&ranking), ["car4way", "Bolt"]);
+        assert_eq!(cheapest_names(&ranking[1..]), ["Bolt"]);
+        assert!(cheapest_names(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_baseline_delta() {
+        let ranking = [
+            ("Bolt", result("Comfort", &[(180.0, "jízda")])),
+            ("car4way", result("Legend", &[(249.0, "2 hodiny + 10 km")])),
+            ("taxi", result("taxi", &[(369.0, "jízda")])),
+        ]
+        .map(|(provider, result)| CalculationResult { provider: Some(provider), ..result });
+
+        assert_eq!(baseline_delta_czk(&ranking, "car4way", "taxi"), Some(120.0));
+        assert_eq!(baseline_delta_czk(&ranking, "car4way", "Bolt"), Some(-69.0));
+        assert_eq!(baseline_delta_czk(&ranking, "car4way", "car4way"), Some(0.0));
+        // Disabled (or otherwise not compared) providers are not in the ranking.
+        assert_eq!(baseline_delta_czk(&ranking, "car4way", "Anytime"), None);
+        assert_eq!(baseline_delta_czk(&ranking, "Anytime", "taxi"), None);
+    }
+
+    #[test]
+    fn test_trip_ending_before_begin_is_not_compared() {
+        with_signals(|| {
+            let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+            let input_data = TripInputData::from_times(2.0, begin, begin - 1.minute());
+            let walk = Provider::new(Signal::new(true), Signal::new(Walk::default()));
+
+            assert_eq!(
+                walk.unavailable_reason(input_data).as_deref(),
+                Some("konec je před začátkem")
+            );
+            assert!(compare_all(std::slice::from_ref(&walk), &[input_data]).is_empty());
+
+            // Not even calculated, whichever way it is asked for.
+            let bolt = Provider::new(Signal::new(true), Signal::new(Bolt::default()));
+            let error = |result: Result<CalculationResult>| result.unwrap_err().to_string();
+            assert_eq!(error(bolt.calculate(input_data)), "konec je před začátkem");
+            assert_eq!(error(bolt.calculate_segments(&[input_data])), "konec je před začátkem");
+            assert!(bolt.shown_result(&[input_data], false).unwrap().is_err());
+
+            let far = TripInputData::from_times(50.0, begin, begin + 1.hour());
+            assert_eq!(
+                error(walk.calculate_segments(&[far])),
+                walk.unavailable_reason(far).unwrap()
+            );
+        })
+    }
+
+    #[test]
+    fn test_sort_by_ranking() {
+        with_signals(|| {
+            let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+            let input_data = TripInputData::from_times(10.0, begin, begin + 30.minutes());
+
+            let car4way = Provider::new(Signal::new(true), Signal::new(Car4way::default()));
+            let own_car = Provider::new(Signal::new(true), Signal::new(OwnCar::default()));
+            let disabled_bolt = Provider::new(Signal::new(false), Signal::new(Bolt::default()));
+            // Too far to walk.
+            let walk = Provider::new(Signal::new(true), Signal::new(Walk::default()));
+
+            let providers = [walk, disabled_bolt, car4way, own_car];
+            let ranking = compare_all(&providers, &[input_data]);
+            let sorted = sort_by_ranking(&providers, &ranking);
+            let names: Vec<_> = sorted.iter().map(Provider::name).collect();
+            assert_eq!(names, ["vlastní auto", "car4way", "pěšky/na kole", "Bolt"]);
+        })
+    }
+
+    #[test]
+    fn test_move_provider() {
+        with_signals(|| {
+            let mut providers = vec![
+                Provider::new(Signal::new(true), Signal::new(Bolt::default())),
+                Provider::new(Signal::new(true), Signal::new(Car4way::default())),
+                Provider::new(Signal::new(true), Signal::new(Walk::default())),
+            ];
+            let mut move_and_list = |index, direction| -> Vec<_> {
+                move_provider(&mut providers, index, direction);
+                providers.iter().map(Provider::name).collect()
+            };
+
+            assert_eq!(move_and_list(0, Direction::Down), ["car4way", "Bolt", "pěšky/na kole"]);
+            assert_eq!(move_and_list(2, Direction::Up), ["car4way", "pěšky/na kole", "Bolt"]);
+            assert_eq!(move_and_list(0, Direction::Up), ["car4way", "pěšky/na kole", "Bolt"]);
+            assert_eq!(move_and_list(2, Direction::Down), ["car4way", "pěšky/na kole", "Bolt"]);
+        })
+    }
+
+    #[test]
+    fn test_disabled_provider_shown_but_not_compared() {
+        with_signals(|| {
+            let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+            let input_data = TripInputData::from_times(10.0, begin, begin + 1.hour());
+            let car4way = Provider::new(Signal::new(false), Signal::new(Car4way::default()));
+
+            assert!(car4way.shown_result(&[input_data], false).is_none());
+            let shown = car4way.shown_result(&[input_data], true).unwrap().unwrap();
+            assert!(shown.total_czk() > 0.0);
+            assert!(compare_all(&[car4way], &[input_data]).is_empty());
+        })
+    }
+
+    #[test]
+    fn test_dispatch_to_provider_impl() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(10.3, begin, begin + 2.hours());
+
+        with_signals(|| {
+            let car4way_signal = Signal::new(Car4way::default());
+            let car4way = Provider::new(Signal::new(true), car4way_signal);
+            assert_eq!((car4way.name(), car4way.id()), ("car4way", "car4way"));
+            assert!(car4way.supports_packages());
+            let expected = car4way_signal.read().calculate(input_data).unwrap();
+            let result = car4way.calculate(input_data).unwrap();
+            assert_eq!(
+                result.total_czk(),
+                expected.total_czk().round(),
+                "invoice rounding applied"
+            );
+            assert_eq!(result.provider_name(), "car4way");
+
+            let bolt = Provider::new(Signal::new(true), Signal::new(Bolt::default()));
+            assert_eq!((bolt.name(), bolt.id()), ("Bolt", "bolt"));
+            assert!(!bolt.supports_packages());
+            assert_eq!(bolt.invoice_rounding(), InvoiceRounding::Haler);
+            let expected = Bolt::default().calculate(input_data);
+            assert_eq!(expected.provider, None);
+            let result = bolt.calculate(input_data).unwrap();
+            assert_eq!(result, CalculationResult { provider: Some("Bolt"), ..expected });
+
+            assert_eq!(car4way, Provider::new(car4way.enabled, car4way_signal));
+            assert_ne!(car4way, Provider::new(car4way.enabled, Signal::new(Car4way::default())));
+            assert_ne!(car4way, Provider::new(car4way.enabled, Signal::new(Walk::default())));
+        })
+    }
+
+    #[test]
+    fn test_segments_across_night() {
+        let evening = date(2025, 6, 3).at(19, 0, 0, 0);
+        let night = date(2025, 6, 3).at(21, 0, 0, 0);
+        let segments = [
+            TripInputData::from_times(0.0, evening, evening + 30.minutes()),
+            TripInputData::from_times(0.0, night, night + 30.minutes()),
+        ];
+
+        with_signals(|| {
+            let car4way = Provider::new(Signal::new(true), Signal::new(Car4way::default()));
+            let [day_czk, night_czk] =
+                segments.map(|segment| car4way.calculate(segment).unwrap().total_czk());
+            assert!(night_czk > day_czk, "{night_czk} at night, {day_czk} by day");
+
+            let result = car4way.calculate_segments(&segments).unwrap();
+            assert_eq!(result.total_czk(), day_czk + night_czk);
+            assert_eq!(result.total_czk(), 210.0 + 240.0);
+            assert!(result.components.iter().any(|c| c.name.starts_with("1. úsek: ")));
+            assert!(result.components.iter().any(|c| c.name.starts_with("2. úsek: ")));
+            assert!(result.components.iter().any(|c| c.description.is_some()), "descriptions kept");
+            assert_eq!(car4way.calculate_segments(&segments[..1]).unwrap().total_czk(), day_czk);
+            assert!(car4way.calculate_segments(&[]).is_err());
+
+            let invalid = TripInputData::from_times(0.0, night, evening);
+            let error = car4way.calculate_segments(&[segments[0], invalid]).unwrap_err();
+            assert_eq!(error.to_string(), "2. úsek: konec je před začátkem");
+
+            let walk = Provider::new(Signal::new(true), Signal::new(Walk::default()));
+            let ranking = compare_all(&[car4way, walk], &segments);
+            assert_eq!(ranking.len(), 2);
+            assert_eq!(ranking[1].total_czk(), 450.0);
+            assert_eq!(ranking[1].provider, Some("car4way"));
+        })
+    }
+
+    #[test]
+    fn test_branding() {
+        with_signals(|| {
+            let car4way = Provider::new(Signal::new(true), Signal::new(Car4way::default()));
+            let branding = car4way.branding();
+            assert!(branding.logo.is_some());
+            assert_eq!(branding.header_style(), "border-bottom: 3px solid #00a7e1;");
+
+            let walk = Provider::new(Signal::new(true), Signal::new(Walk::default()));
+            assert_eq!(walk.branding(), Branding::default());
+            assert_eq!(walk.branding().header_style(), "border-bottom: 3px solid gray;");
+        })
+    }
+
+    #[test]
+    fn test_cheapest_summary() {
+        let mut car4way = CalculationResult::new("Legend");
+        car4way.add_component(2100.0, "Víkend + 200 km");
+        car4way.package = Some("Víkend + 200 km".into());
+        car4way.provider = Some("car4way");
+        let mut bolt = CalculationResult::new("Go");
+        bolt.add_component(2145.0, "minuty");
+        bolt.provider = Some("Bolt");
+        let mut taxi = CalculationResult::new("taxi");
+        taxi.add_component(3000.0, "jízdné");
+        taxi.provider = Some("taxi");
+
+        let results = [car4way, bolt, taxi];
+        let summary = CheapestSummary::new(&results).unwrap();
+        assert_eq!(summary.winner, "car4way");
+        assert_eq!(summary.runner_up.as_deref(), Some("Bolt"));
+        assert_eq!(summary.margin_czk, 45.0);
+        assert_eq!(
+            summary.text(Lang::Cs),
+            "nejlevnější je car4way, o 45\u{a0}Kč před Bolt (díky balíčku Víkend + 200 km)"
+        );
+        assert_eq!(
+            summary.text(Lang::En),
+            "car4way is the cheapest, 45\u{a0}Kč ahead of Bolt (thanks to the Víkend + 200 km \
+             package)"
+        );
+
+        assert_eq!(CheapestSummary::new(&[]), None);
+    }
+
+    #[test]
+    fn test_insurance() {
+        let insurance = Insurance { enabled: true, per_day_czk: 199.0 };
+        let insured = |begin: DateTime, end| {
+            let mut result = CalculationResult::new("Legend");
+            insurance.apply(TripInputData::from_times(0.0, begin, end), &mut result);
+            result.components.into_iter().map(|c| (c.name, c.czk)).collect::<Vec<_>>()
+        };
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+
+        assert_eq!(insured(begin, begin + 1.hour()), [("pojištění 1 den".into(), 199.0)]);
+        assert_eq!(insured(begin, begin + 1.day()), [("pojištění 1 den".into(), 199.0)]);
+        assert_eq!(insured(begin, begin + 49.hours()), [("pojištění 3 dny".into(), 597.0)]);
+        assert_eq!(insured(begin, begin + 5.days()), [("pojištění 5 dní".into(), 995.0)]);
+        assert_eq!(insured(begin, begin), []);
+        assert_eq!(insured(begin, begin - 1.hour()), []);
+        // Spring DST transition: 24 wall-clock hours are just 23 real ones.
+        let spring = date(2025, 3, 29).at(12, 0, 0, 0);
+        assert_eq!(insured(spring, spring + 24.hours()), [("pojištění 1 den".into(), 199.0)]);
+        // Autumn one: 24 wall-clock hours are 25 real ones.
+        let autumn = date(2025, 10, 25).at(12, 0, 0, 0);
+        assert_eq!(insured(autumn, autumn + 24.hours()), [("pojištění 2 dny".into(), 398.0)]);
+    }
+
+    #[test]
+    fn test_discount() {
+        let subtotal = || result("Legend", &[(400.0, "jízda"), (100.0, "pojištění 1 den")]);
+        let discounted = |kind, amount| {
+            let mut result = subtotal();
+            Discount { kind, amount }.apply(&mut result);
+            result
+        };
+
+        let percent = discounted(DiscountKind::Percent, 10.0);
+        assert_eq!(
+            percent.components.last().map(|c| (c.czk, c.name.as_str())),
+            Some((-50.0, "sleva"))
+        );
+        assert_eq!(percent.total_czk(), 450.0);
+        assert_eq!(discounted(DiscountKind::Czk, 50.0).total_czk(), 450.0);
+        assert_eq!(discounted(DiscountKind::Czk, 800.0).total_czk(), 0.0, "clamped at zero");
+        assert_eq!(discounted(DiscountKind::None, 50.0), subtotal());
+        assert_eq!(discounted(DiscountKind::Czk, 0.0), subtotal());
+    }
+
+    #[test]
+    fn test_discount_of_segments() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let discount = Discount { kind: DiscountKind::Czk, amount: 50.0 };
+        let first = TripInputData {
+            discount,
+            ..TripInputData::from_times(2.0, begin, begin + 30.minutes())
+        };
+        let segments =
+            first.itinerary(&[TripInputData::from_times(2.0, begin + 1.hour(), begin + 2.hours())]);
+
+        with_signals(|| {
+            let taxi = Provider::new(Signal::new(true), Signal::new(Taxi::default()));
+            let full_czk: f64 = segments
+                .iter()
+                .map(|segment| Taxi::default().calculate(*segment).total_czk().round())
+                .sum();
+            let result = taxi.calculate_segments(&segments).unwrap();
+            assert_eq!(result.total_czk(), full_czk - 50.0, "discounted once");
+            assert_eq!(result.components.last().unwrap().name, "sleva");
+            assert_eq!(
+                taxi.calculate(first).unwrap().total_czk(),
+                Taxi::default().calculate(first).total_czk().round() - 50.0
+            );
+        })
+    }
+
+    #[test]
+    fn test_insurance_two_days() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(0.0, begin, begin + 47.hours());
+
+        let mut result = CalculationResult::new("Legend");
+        Insurance::new(199.0).apply(input_data, &mut result);
+        assert!(result.components.is_empty(), "disabled insurance should not be billed");
+
+        let insurance = Insurance { enabled: true, per_day_czk: 199.0 };
+        insurance.apply(input_data, &mut result);
+        assert_eq!(
+            result.components,
+            [PriceComponent {
+                czk: 2.0 * 199.0,
+                name: "pojištění 2 dny".into(),
+                description: None
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rounding() {
+        let duration = SignedDuration::new(30 * 60 + 30, 0);
+        assert_eq!(Rounding::Pessimistic.minutes(duration), 31);
+        assert_eq!(Rounding::Optimistic.minutes(duration), 30);
+        assert_eq!(Rounding::Pessimistic.minutes(SignedDuration::from_mins(30)), 30);
+        assert_eq!(Rounding::Pessimistic.round(3.4), 4.0);
+        assert_eq!(Rounding::Optimistic.round(3.4), 3.0);
+    }
+
+    fn result(car_type: &str, components: &[(f64, &str)]) -> CalculationResult {
+        let mut result = CalculationResult::new(car_type);
+        for (czk, name) in components {
+            result.add_component(*czk, *name);
+        }
+        result
+    }
+
+    #[test]
+    fn test_calculation_result_contract() {
+        let with_discount =
+            result("Legend", &[(249.0, "balíček"), (47.45, "extra za 5 km"), (-50.0, "sleva")]);
+        assert_eq!(with_discount.total_czk(), 246.45);
+        assert_eq!(result("Legend", &[]).total_czk(), 0.0);
+
+        let cheap = result("Legend", &[(100.0, "minuty")]);
+        let same_price = result("Fancy", &[(60.0, "minuty"), (40.0, "km")]);
+        let expensive = result("Boss", &[(300.0, "minuty")]);
+
+        assert_eq!(cheap.cmp_price(&same_price), Ordering::Equal);
+        assert_eq!(cheap.cmp_price(&with_discount), Ordering::Less);
+        assert_eq!(expensive.cmp_price(&with_discount), Ordering::Greater);
+        assert_eq!(cheap, cheap.clone());
+
+        // Stable: equally priced results keep their order.
+        let mut sorted = [expensive, same_price, with_discount, cheap];
+        sorted.sort_by(CalculationResult::cmp_price);
+        let car_types: Vec<_> = sorted.iter().map(|result| result.car_type.as_str()).collect();
+        assert_eq!(car_types, ["Fancy", "Legend", "Legend", "Boss"]);
+    }
+
+    #[test]
+    fn test_invoice_rounding_changes_ranking() {
+        let whole_crown = result("car4way", &[(100.6, "minuty")]);
+        let haler = result("Bolt", &[(100.9, "minuty")]);
+        assert!(
+            whole_crown.total_czk() < haler.total_czk(),
+            "raw floats rank the whole-crown provider first"
+        );
+
+        let mut rounded_whole_crown = whole_crown.clone();
+        InvoiceRounding::WholeCrown.apply(&mut rounded_whole_crown);
+        let mut rounded_haler = haler.clone();
+        InvoiceRounding::Haler.apply(&mut rounded_haler);
+
+        assert_eq!(rounded_whole_crown.total_czk(), 101.0);
+        assert_eq!(rounded_whole_crown.components[1].name, "zaokrouhlení");
+        assert_eq!(rounded_haler, haler, "already whole halers, no rounding component");
+        assert!(
+            rounded_haler.total_czk() < rounded_whole_crown.total_czk(),
+            "invoiced totals rank the other way"
+        );
+    }
+
+    #[test]
+    fn test_out_of_zone_return_fee() {
+        let mut result = result("Legend", &[(100.0, "minuty")]);
+        ReturnLocation::InZone.apply_fee(300.0, &mut result);
+        assert_eq!(result.total_czk(), 100.0);
+
+        ReturnLocation::OutOfZone.apply_fee(300.0, &mut result);
+        assert_eq!(result.total_czk(), 400.0);
+        assert_eq!(result.components[1].name, "vrácení mimo zónu");
+    }
+
+    #[test]
+    fn test_add_component() {
+        let mut result = CalculationResult::new("Legend");
+        result.add_component(249.0, "2 hodiny + 10 km");
+        result.add_component(12.5, String::from("extra za 2.5 km"));
+
+        assert_eq!(result.car_type, "Legend");
+        assert_eq!(
+            result.components,
+            [
+                PriceComponent { czk: 249.0, name: "2 hodiny + 10 km".into(), description: None },
+                PriceComponent { czk: 12.5, name: "extra za 2.5 km".into(), description: None },
+            ]
+        );
+        assert_eq!(result.total_czk(), 261.5);
+    }
+
+    #[test]
+    fn test_over_budget() {
+        let result = result("Legend", &[(249.0, "2 hodiny + 10 km"), (51.0, "extra za 5 km")]);
+
+        assert_eq!(result.over_budget_czk(500.0), None);
+        assert_eq!(result.over_budget_czk(300.0), None, "exactly on budget is within");
+        assert_eq!(result.over_budget_czk(299.0), Some(1.0));
+        assert_eq!(result.over_budget_czk(0.0), Some(300.0));
+    }
+
+    #[test]
+    fn test_per_person() {
+        let result = result("Legend", &[(249.0, "2 hodiny + 10 km"), (51.0, "extra za 5 km")]);
+
+        assert_eq!(result.per_person_czk(1), 300.0);
+        assert_eq!(result.per_person_czk(4), 75.0);
+        assert_eq!(result.total_czk(), 300.0, "total stays for the whole car");
+        assert_eq!(result.per_person_czk(0), 300.0);
+    }
+
+    #[test]
+    fn test_unit_prices() {
+        let result = result("Legend", &[(249.0, "2 hodiny + 10 km"), (51.0, "extra za 5 km")]);
+
+        assert_eq!(result.per_km_czk(15.0), Some(20.0));
+        assert_eq!(result.per_km_czk(0.0), None);
+        assert_eq!(result.per_hour_czk(SignedDuration::from_mins(90)), Some(200.0));
+        assert_eq!(result.per_hour_czk(SignedDuration::ZERO), None);
+        assert_eq!(result.per_hour_czk(SignedDuration::from_mins(-30)), None);
+    }
+
+    #[test]
+    fn test_mixed_sign_components() {
+        let result = result(
+            "Legend",
+            &[
+                (249.0, "2 hodiny + 10 km"),
+                (-50.0, "sleva pro nové zákazníky"),
+                (0.0, "parkování v zóně zdarma"),
+                (100.0, "příjezd na letiště"),
+            ],
+        );
+        assert_eq!(result.total_czk(), 299.0);
+
+        let groups: Vec<_> = result
+            .grouped_components()
+            .into_iter()
+            .map(|(group, components)| {
+                (group, components.iter().map(|c| c.name.as_str()).collect::<Vec<_>>())
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                (ComponentGroup::Fee, vec!["2 hodiny + 10 km", "příjezd na letiště"]),
+                (ComponentGroup::Discount, vec!["sleva pro nové zákazníky"]),
+                (ComponentGroup::Informational, vec!["parkování v zóně zdarma"]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use crate::{
+    TripInputData,
+    i18n::{Key, t, use_lang},
+    provider::{CalculationResult, ProviderImpl, car4way::deserialize_decimal_comma, elapsed},
+};
+use anyhow::{Context, Result, anyhow, bail};
+use csv::{ReaderBuilder, Trim};
+use dioxus::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use tracing::{debug, error};
+
+const TARIFF_DATA: &[u8] = include_bytes!("../../provider-data/anytime/tariff.tsv");
+
+/// Like Car4way ones, a broken tariff makes the provider unavailable instead of crashing the app.
+static TARIFF: LazyLock<Result<Tariff, String>> = LazyLock::new(|| {
+    load_tariff(TARIFF_DATA).context("loading Anytime tariff").map_err(|e| {
+        error!("{e:#}");
+        format!("{e:#}")
+    })
+});
+
+/// Anytime car sharing, billed per minute and per kilometer unless a package covers them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Anytime {}
+
+impl ProviderImpl for Anytime {
+    fn name(&self) -> &'static str {
+        "Anytime"
+    }
+
+    fn id(&self) -> &'static str {
+        "anytime"
+    }
+
+    fn calculate(&self, input_data: TripInputData) -> Result<CalculationResult> {
+        Anytime::calculate(self, input_data)
+    }
+
+    fn unavailable_reason(&self, _input_data: TripInputData) -> Option<String> {
+        TARIFF.as_ref().err().map(|e| format!("nelze: ceník se nepodařilo načíst: {e}"))
+    }
+
+    fn supports_packages(&self) -> bool {
+        true
+    }
+
+    fn render_input(_anytime: Signal<Self>, _input_data: TripInputData) -> Element {
+        rsx! { AnytimeNote {} }
+    }
+}
+
+/// Why the provider is disabled by default.
+#[component]
+fn AnytimeNote() -> Element {
+    let lang = use_lang();
+    rsx! {
+        p { class: "note", {t(Key::UnverifiedPrices, lang)} }
+    }
+}
+
+impl Anytime {
+    pub fn calculate(&self, input_data: TripInputData) -> Result<CalculationResult> {
+        debug!("Anytime::calculate({input_data:?}) called");
+        let tariff = TARIFF.as_ref().map_err(|e| anyhow!("ceník se nepodařilo načíst: {e}"))?;
+        let packages = tariff.packages.iter().filter(|_| input_data.packages);
+        Ok(packages
+            .map(Some)
+            .chain([None])
+            .map(|package| tariff.calculate_for_package(input_data, package))
+            .min_by(CalculationResult::cmp_price)
+            .expect("at least the no-package variant is present"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Tariff {
+    per_minute_czk: f64,
+    per_km_czk: f64,
+    packages: Vec<Package>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Package {
+    name: String,
+    minutes: i64,
+    kilometers: f64,
+    czk: f64,
+}
+
+impl Tariff {
+    /// Bill the trip using `package` (if any) and the rest at the per-minute and per-km rates.
+    fn calculate_for_package(
+        &self,
+        input_data: TripInputData,
+        package: Option<&Package>,
+    ) -> CalculationResult {
+        let rounding = input_data.rounding;
+        let mut minutes = rounding.minutes(elapsed(input_data.begin, input_data.end)).max(0);
+        let mut km = rounding.round(input_data.km);
+
+        let mut result = CalculationResult::new("osobní vůz");
+        if let Some(package) = package {
+            result.add_component(package.czk, package.name.clone());
+            result.package = Some(package.name.clone());
+            minutes = (minutes - package.minutes).max(0);
+            km = (km - package.kilometers).max(0.0);
+        }
+        if minutes > 0 || package.is_none() {
+            result.add_component(
+                minutes as f64 * self.per_minute_czk,
+                format!("jízda {minutes} minut"),
+            );
+        }
+        if km > 0.0 || package.is_none() {
+            result.add_component(km * self.per_km_czk, format!("{km} km"));
+        }
+        result
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TariffRow {
+    #[serde(rename = "Položka")]
+    item: String,
+    #[serde(rename = "Cena", deserialize_with = "deserialize_decimal_comma")]
+    czk: Option<f64>,
+}
+
+fn load_tariff(data: &[u8]) -> Result<Tariff> {
+    static PACKAGE_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new("^([0-9]+) (hodin[ay]?|den|dny|dní) \\+ ([0-9]+) km$").unwrap()
+    });
+
+    let mut per_minute_czk = None;
+    let mut per_km_czk = None;
+    let mut packages = vec![];
+
+    let mut rdr = ReaderBuilder::new().delimiter(b'\t').trim(Trim::All).from_reader(data);
+    for result in rdr.deserialize() {
+        let row: TariffRow = result?;
+        debug!("{row:?}");
+        let czk = row.czk.with_context(|| format!("no price for item {}", row.item))?;
+
+        if row.item == "Minuta" {
+            per_minute_czk = Some(czk);
+        } else if row.item == "Km" {
+            per_km_czk = Some(czk);
+        } else if let Some(matches) = PACKAGE_RE.captures(&row.item) {
+            let count: i64 = matches[1].parse().context("parsing package duration")?;
+            let unit_minutes = if matches[2].starts_with('h') { 60 } else { 24 * 60 };
+            if count == 0 {
+                bail!("Package {} has zero duration", row.item);
+            }
+            packages.push(Package {
+                name: row.item.clone(),
+                minutes: count * unit_minutes,
+                kilometers: matches[3].parse().context("parsing package kilometers")?,
+                czk,
+            });
+        } else {
+            bail!("The item {:?} doesn't match any pattern.", row.item);
+        }
+    }
+
+    Ok(Tariff {
+        per_minute_czk: per_minute_czk.context("per minute price not parsed")?,
+        per_km_czk: per_km_czk.context("per km price not parsed")?,
+        packages,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::{ToSpan, civil::date};
+    use test_log::test;
+
+    #[test]
+    fn test_load_tariff() {
+        let tariff = load_tariff(TARIFF_DATA).unwrap();
+        assert_eq!((tariff.per_minute_czk, tariff.per_km_czk), (4.9, 4.9));
+        let names: Vec<_> = tariff.packages.iter().map(|package| package.name.as_str()).collect();
+        assert_eq!(names, ["3 hodiny + 50 km", "24 hodin + 150 km", "3 dny + 400 km"]);
+        assert_eq!(
+            tariff.packages[1],
+            Package {
+                name: "24 hodin + 150 km".into(),
+                minutes: 24 * 60,
+                kilometers: 150.0,
+                czk: 1590.0,
+            }
+        );
+
+        let error = load_tariff("Položka\tCena\nMinuta\t4,90\n1 týden + 500 km\t5000\n".as_bytes())
+            .unwrap_err();
+        assert_eq!(error.to_string(), "The item \"1 týden + 500 km\" doesn't match any pattern.");
+    }
+
+    #[test]
+    fn test_calculate() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let anytime = Anytime::default();
+
+        let short = anytime
+            .calculate(TripInputData::from_times(10.0, begin, begin + 30.minutes()))
+            .unwrap();
+        assert_eq!(short.package, None);
+        assert_eq!(short.total_czk(), 30.0 * 4.9 + 10.0 * 4.9);
+
+        let day = TripInputData::from_times(180.0, begin, begin + 1.day());
+        let result = anytime.calculate(day).unwrap();
+        assert_eq!(result.package.as_deref(), Some("24 hodin + 150 km"));
+        let components: Vec<_> = result.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(components, ["24 hodin + 150 km", "30 km"]);
+        assert_eq!(result.total_czk(), 1590.0 + 30.0 * 4.9);
+
+        let without_packages = anytime.calculate(TripInputData { packages: false, ..day }).unwrap();
+        assert_eq!(without_packages.package, None);
+        assert!(without_packages.total_czk() > result.total_czk());
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    FormEvent, TripInputData,
+    provider::{CalculationResult, ProviderImpl, elapsed},
+};
+use anyhow::Result;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+
+/// Shared bikes (Nextbike, Rekola), billed per started block of minutes after a free start. The
+/// distance doesn't matter.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bike {
+    operator: BikeOperator,
+}
+
+impl ProviderImpl for Bike {
+    fn name(&self) -> &'static str {
+        "kolo"
+    }
+
+    fn id(&self) -> &'static str {
+        "bike"
+    }
+
+    fn calculate(&self, input_data: TripInputData) -> Result<CalculationResult> {
+        Ok(Bike::calculate(self, input_data))
+    }
+
+    fn render_input(bike: Signal<Self>, _input_data: TripInputData) -> Element {
+        rsx! { BikeInput { bike } }
+    }
+}
+
+impl Bike {
+    pub fn calculate(&self, input_data: TripInputData) -> CalculationResult {
+        let rates = self.operator.rates();
+        let minutes = input_data.rounding.minutes(elapsed(input_data.begin, input_data.end));
+        let paid_minutes = (minutes - rates.free_minutes).max(0).unsigned_abs();
+        let blocks = paid_minutes.div_ceil(rates.block_minutes);
+
+        let mut result = CalculationResult::new(self.operator.label());
+        result.add_component(rates.unlock_czk, "odemčení");
+        if rates.free_minutes > 0 {
+            result.add_component(0.0, format!("prvních {} minut zdarma", rates.free_minutes));
+        }
+        if blocks > 0 {
+            result.add_component(
+                blocks as f64 * rates.per_block_czk,
+                format!("{blocks}× {} minut", rates.block_minutes),
+            );
+        }
+        result
+    }
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumIter,
+    Display,
+    EnumString,
+    Serialize,
+    Deserialize,
+)]
+pub enum BikeOperator {
+    #[default]
+    Nextbike,
+    Rekola,
+}
+
+impl BikeOperator {
+    fn label(&self) -> &'static str {
+        match self {
+            BikeOperator::Nextbike => "Nextbike",
+            BikeOperator::Rekola => "Rekola",
+        }
+    }
+
+    /// Prague prices of a single ride without a subscription.
+    fn rates(&self) -> BikeRates {
+        match self {
+            BikeOperator::Nextbike => BikeRates {
+                unlock_czk: 0.0,
+                free_minutes: 15,
+                block_minutes: 15,
+                per_block_czk: 25.0,
+            },
+            BikeOperator::Rekola => BikeRates {
+                unlock_czk: 10.0,
+                free_minutes: 15,
+                block_minutes: 15,
+                per_block_czk: 20.0,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BikeRates {
+    unlock_czk: f64,
+    free_minutes: i64,
+    block_minutes: u64,
+    per_block_czk: f64,
+}
+
+#[component]
+pub fn BikeInput(bike: Signal<Bike>) -> Element {
+    let name = bike.read().name();
+
+    let operator_changed = move |evt: FormEvent| {
+        bike.write().operator = evt.parsed()?;
+        Ok(())
+    };
+
+    let current = *bike.read();
+    let rates = current.operator.rates();
+
+    rsx! {
+        p {
+            label { for: "provider-{name}-operator", "Provozovatel: " },
+            select { id: "provider-{name}-operator",
+                onchange: operator_changed,
+                for operator in BikeOperator::iter() {
+                    option { value: "{operator}",
+                        selected: current.operator == operator,
+                        "{operator.label()}"
+                    }
+                }
+            }
+        }
+        p { class: "note",
+            "odemčení {rates.unlock_czk} Kč, prvních {rates.free_minutes} minut zdarma, "
+            "pak {rates.per_block_czk} Kč za každých {rates.block_minutes} minut"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::{ToSpan, civil::date};
+    use test_log::test;
+
+    fn total_czk(operator: BikeOperator, minutes: i64) -> f64 {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(100.0, begin, begin + minutes.minutes());
+        Bike { operator }.calculate(input_data).total_czk()
+    }
+
+    #[test]
+    fn test_calculate() {
+        assert_eq!(total_czk(BikeOperator::Nextbike, 10), 0.0);
+        assert_eq!(total_czk(BikeOperator::Nextbike, 15), 0.0);
+        assert_eq!(total_czk(BikeOperator::Nextbike, 20), 25.0);
+        assert_eq!(total_czk(BikeOperator::Nextbike, 2 * 60), 7.0 * 25.0);
+
+        assert_eq!(total_czk(BikeOperator::Rekola, 10), 10.0);
+        assert_eq!(total_czk(BikeOperator::Rekola, 20), 10.0 + 20.0);
+        assert_eq!(total_czk(BikeOperator::Rekola, 2 * 60), 10.0 + 7.0 * 20.0);
+    }
+
+    #[test]
+    fn test_components() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(3.0, begin, begin + 40.minutes());
+        let result = Bike::default().calculate(input_data);
+        let names: Vec<_> = result.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["odemčení", "prvních 15 minut zdarma", "2× 15 minut"]);
+    }
+}
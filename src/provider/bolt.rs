@@ -1,8 +1,298 @@
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
-pub struct Bolt {}
+use crate::{
+    FormEvent, TripInputData,
+    provider::{Branding, CalculationResult, InvoiceRounding, ProviderImpl, elapsed},
+};
+use anyhow::Result;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
 
-impl Bolt {
-    pub fn name(&self) -> &'static str {
+/// Bolt Drive car sharing, billed per started minute (cheaper while paused) and per kilometer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bolt {
+    category: BoltCategory,
+    /// User-entered driving price per minute instead of the one of [`Bolt::category`].
+    driving_per_minute_czk: Option<f64>,
+    /// User-entered price per km instead of the one of [`Bolt::category`].
+    per_km_czk: Option<f64>,
+    /// Minimum fare of a rental, if any.
+    min_charge_czk: Option<f64>,
+}
+
+impl ProviderImpl for Bolt {
+    fn name(&self) -> &'static str {
         "Bolt"
     }
+
+    fn id(&self) -> &'static str {
+        "bolt"
+    }
+
+    fn calculate(&self, input_data: TripInputData) -> Result<CalculationResult> {
+        Ok(Bolt::calculate(self, input_data))
+    }
+
+    fn branding(&self) -> Branding {
+        Branding { logo: Some(asset!("/assets/logos/bolt.svg")), color: Some("#34d186") }
+    }
+
+    fn invoice_rounding(&self) -> InvoiceRounding {
+        InvoiceRounding::Haler
+    }
+
+    fn render_input(bolt: Signal<Self>, _input_data: TripInputData) -> Element {
+        rsx! { BoltInput { bolt } }
+    }
+}
+
+impl Bolt {
+    fn rates(&self) -> BoltRates {
+        let rates = self.category.rates();
+        BoltRates {
+            driving_per_minute_czk: self
+                .driving_per_minute_czk
+                .unwrap_or(rates.driving_per_minute_czk),
+            per_km_czk: self.per_km_czk.unwrap_or(rates.per_km_czk),
+            ..rates
+        }
+    }
+
+    pub fn calculate(&self, input_data: TripInputData) -> CalculationResult {
+        let rates = self.rates();
+        let rounding = input_data.rounding;
+        // An end before the begin bills no time (and keeps the clamp below valid).
+        let minutes = rounding.minutes(elapsed(input_data.begin, input_data.end)).max(0);
+        let paused_minutes = input_data.idle_minutes.clamp(0, minutes);
+        let driving_minutes = minutes - paused_minutes;
+        let km = rounding.round(input_data.km);
+
+        let mut result = CalculationResult::new(self.category.label());
+        result.add_component(rates.unlock_czk, "odemčení");
+        result.add_component(
+            driving_minutes as f64 * rates.driving_per_minute_czk,
+            format!("jízda {driving_minutes} minut"),
+        );
+        if paused_minutes > 0 {
+            result.add_component(
+                paused_minutes as f64 * rates.paused_per_minute_czk,
+                format!("pozastavení {paused_minutes} minut"),
+            );
+        }
+        result.add_component(km * rates.per_km_czk, format!("{km} km"));
+        if let Some(min_charge_czk) = self.min_charge_czk {
+            result.top_up_to(min_charge_czk);
+        }
+        result
+    }
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumIter,
+    Display,
+    EnumString,
+    Serialize,
+    Deserialize,
+)]
+pub enum BoltCategory {
+    #[default]
+    Go,
+    XL,
+    Premium,
+}
+
+impl BoltCategory {
+    fn label(&self) -> &'static str {
+        match self {
+            BoltCategory::Go => "Go (Fabia)",
+            BoltCategory::XL => "XL (Octavia Combi)",
+            BoltCategory::Premium => "Premium (Superb)",
+        }
+    }
+
+    /// Prague prices of the category.
+    fn rates(&self) -> BoltRates {
+        match self {
+            BoltCategory::Go => BoltRates {
+                unlock_czk: 19.0,
+                driving_per_minute_czk: 4.9,
+                paused_per_minute_czk: 2.9,
+                per_km_czk: 4.9,
+            },
+            BoltCategory::XL => BoltRates {
+                unlock_czk: 19.0,
+                driving_per_minute_czk: 5.9,
+                paused_per_minute_czk: 3.5,
+                per_km_czk: 5.9,
+            },
+            BoltCategory::Premium => BoltRates {
+                unlock_czk: 29.0,
+                driving_per_minute_czk: 7.9,
+                paused_per_minute_czk: 4.9,
+                per_km_czk: 6.9,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BoltRates {
+    unlock_czk: f64,
+    driving_per_minute_czk: f64,
+    paused_per_minute_czk: f64,
+    per_km_czk: f64,
+}
+
+#[component]
+pub fn BoltInput(bolt: Signal<Bolt>) -> Element {
+    let name = bolt.read().name();
+
+    let category_changed = move |evt: FormEvent| {
+        bolt.write().category = evt.parsed()?;
+        Ok(())
+    };
+    let driving_per_minute_changed = move |evt: FormEvent| {
+        let value = evt.value();
+        bolt.write().driving_per_minute_czk =
+            if value.is_empty() { None } else { Some(value.parse()?) };
+        Ok(())
+    };
+    let per_km_changed = move |evt: FormEvent| {
+        let value = evt.value();
+        bolt.write().per_km_czk = if value.is_empty() { None } else { Some(value.parse()?) };
+        Ok(())
+    };
+    let min_charge_changed = move |evt: FormEvent| {
+        let value = evt.value();
+        bolt.write().min_charge_czk = if value.is_empty() { None } else { Some(value.parse()?) };
+        Ok(())
+    };
+
+    let current = *bolt.read();
+    let category_rates = current.category.rates();
+
+    rsx! {
+        p {
+            label { for: "provider-{name}-category", "Kategorie: " },
+            select { id: "provider-{name}-category",
+                onchange: category_changed,
+                for category in BoltCategory::iter() {
+                    option { value: "{category}",
+                        selected: current.category == category,
+                        "{category.label()}"
+                    }
+                }
+            }
+        }
+        p {
+            label { for: "provider-{name}-per-minute", "Jízda: " },
+            input { id: "provider-{name}-per-minute",
+                r#type: "number",
+                placeholder: "{category_rates.driving_per_minute_czk}",
+                value: current.driving_per_minute_czk.map(|czk| czk.to_string()),
+                onchange: driving_per_minute_changed,
+                min: 0,
+                step: 0.01,
+            },
+            " Kč/min, ",
+            input { id: "provider-{name}-per-km",
+                r#type: "number",
+                placeholder: "{category_rates.per_km_czk}",
+                value: current.per_km_czk.map(|czk| czk.to_string()),
+                onchange: per_km_changed,
+                min: 0,
+                step: 0.01,
+            },
+            " Kč/km"
+        }
+        p {
+            label { for: "provider-{name}-min-charge", "Minimální cena (Kč): " },
+            input { id: "provider-{name}-min-charge",
+                r#type: "number",
+                placeholder: "žádná",
+                value: current.min_charge_czk.map(|czk| czk.to_string()),
+                onchange: min_charge_changed,
+                min: 0,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::{ToSpan, civil::date};
+    use test_log::test;
+
+    fn input_data() -> TripInputData {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        TripInputData {
+            idle_minutes: 15,
+            ..TripInputData::from_times(12.3, begin, begin + 40.minutes() + 10.seconds())
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let bolt = Bolt::default();
+        let result = bolt.calculate(input_data());
+        let components: Vec<_> =
+            result.components.iter().map(|c| (c.name.as_str(), c.czk)).collect();
+        assert_eq!(
+            components,
+            [
+                ("odemčení", 19.0),
+                ("jízda 26 minut", 26.0 * 4.9),
+                ("pozastavení 15 minut", 15.0 * 2.9),
+                ("13 km", 13.0 * 4.9),
+            ]
+        );
+
+        let without_pause = bolt.calculate(TripInputData { idle_minutes: 0, ..input_data() });
+        assert_eq!(without_pause.components.len(), 3, "no pause component");
+    }
+
+    #[test]
+    fn test_end_before_begin() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData {
+            idle_minutes: 15,
+            ..TripInputData::from_times(2.0, begin, begin - 1.hour())
+        };
+        let result = Bolt::default().calculate(input_data);
+        assert_eq!(result.total_czk(), 19.0 + 2.0 * 4.9);
+    }
+
+    #[test]
+    fn test_categories_and_overrides() {
+        let total = |bolt: Bolt| bolt.calculate(input_data()).total_czk();
+        let go = total(Bolt::default());
+        let xl = total(Bolt { category: BoltCategory::XL, ..Bolt::default() });
+        let premium = total(Bolt { category: BoltCategory::Premium, ..Bolt::default() });
+        assert!(go < xl && xl < premium, "{go} < {xl} < {premium}");
+
+        let overridden =
+            Bolt { driving_per_minute_czk: Some(1.0), per_km_czk: Some(2.0), ..Bolt::default() };
+        assert_eq!(total(overridden), 19.0 + 26.0 * 1.0 + 15.0 * 2.9 + 13.0 * 2.0);
+    }
+
+    #[test]
+    fn test_min_charge() {
+        let bolt = Bolt { min_charge_czk: Some(100.0), ..Bolt::default() };
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+
+        let short = bolt.calculate(TripInputData::from_times(1.0, begin, begin + 5.minutes()));
+        let last = short.components.last().unwrap();
+        assert_eq!(last.name, "doplatek do minima");
+        assert!((last.czk - (100.0 - 19.0 - 5.0 * 4.9 - 4.9)).abs() < 1e-9);
+        assert!((short.total_czk() - 100.0).abs() < 1e-9);
+
+        let above = bolt.calculate(input_data());
+        assert_eq!(above, Bolt::default().calculate(input_data()), "no top-up above the minimum");
+    }
 }
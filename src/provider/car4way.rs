@@ -1,51 +1,342 @@
 use crate::{
     FormEvent, TripInputData,
-    provider::{CalculationResult, PriceComponent},
+    currency::use_currency,
+    format_duration,
+    i18n::{Key, Lang, t, use_lang},
+    provider::{
+        Branding, CalculationResult, Insurance, InvoiceRounding, ProviderImpl, Rounding,
+        TimeWindow, elapsed, now_in_prague,
+    },
 };
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, anyhow, bail, ensure};
 use csv::{ReaderBuilder, Trim};
 use dioxus::prelude::*;
 use enum_map::{Enum, EnumMap, enum_map};
-use jiff::civil::{DateTime, Time, Weekday};
+use jiff::{
+    SignedDuration, Span, ToSpan,
+    civil::{Date, DateTime, Time, Weekday},
+};
 use regex::{Captures, Regex};
-use serde::{Deserialize, Deserializer, de::Error};
-use std::{cmp::min, collections::BTreeSet, mem, sync::LazyLock, time::Duration};
+use rules::{PricingRule, RollingCap, TripState};
+use serde::{Deserialize, Deserializer, Serialize, de::Error};
+use std::{
+    cmp::min,
+    collections::BTreeSet,
+    fmt, mem,
+    sync::{Arc, LazyLock, OnceLock},
+    time::Duration,
+};
 use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
-use tracing::debug;
+use tracing::{debug, error};
 
-const BASIC: &[u8] = include_bytes!("../../provider-data/car4way/basic.tsv");
-const ACTIVE: &[u8] = include_bytes!("../../provider-data/car4way/active.tsv");
-const BUSINESS: &[u8] = include_bytes!("../../provider-data/car4way/business.tsv");
+const BASIC: &[u8] = include_bytes!("../../provider-data/car4way/2025-05-01/basic.tsv");
+const ACTIVE: &[u8] = include_bytes!("../../provider-data/car4way/2025-05-01/active.tsv");
+const BUSINESS: &[u8] = include_bytes!("../../provider-data/car4way/2025-05-01/business.tsv");
+/// Price lists (basic, active and business tariff) by the day they are valid from, oldest first.
+const TARIFF_DATA: [(&str, [&[u8]; 3]); 1] = [("2025-05-01", [BASIC, ACTIVE, BUSINESS])];
 
-static TARIFFS: LazyLock<Vec<Tariff>> = LazyLock::new(load_tariffs);
+mod rules;
 
-#[derive(Debug, Clone, PartialEq)]
+/// Default price of the deductible reduction, adjustable in the UI.
+const INSURANCE_PER_DAY_CZK: f64 = 199.0;
+/// Default fee for returning the car out of the zone, adjustable in the UI.
+const OUT_OF_ZONE_RETURN_CZK: f64 = 500.0;
+/// Longest extension of an ongoing rental offered, a month.
+const MAX_EXTENSION_MINUTES: i64 = 31 * 24 * 60;
+/// Why there's no price when the user unchecks all car types.
+const NO_CAR_TYPES: &str = "žádná kategorie vybrána";
+/// Car4way invoices whole crowns.
+pub const INVOICE_ROUNDING: InvoiceRounding = InvoiceRounding::WholeCrown;
+
+/// An error (formatted, as [`anyhow::Error`] is not `Sync`) makes Car4way unavailable instead of
+/// crashing the whole app on a bad data edit. Tariffs themselves are parsed on first use, see
+/// [`TariffVersion::tariff()`].
+static TARIFFS: LazyLock<Result<Vec<TariffVersion<'static>>, String>> = LazyLock::new(|| {
+    load_tariffs(&TARIFF_DATA).map_err(|e| {
+        error!("{e:#}");
+        format!("{e:#}")
+    })
+});
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Car4way {
     tariff: TariffKind,
     car_types: BTreeSet<CarType>,
+    monthly_usage: MonthlyUsage,
+    insurance: Insurance,
+    out_of_zone_return_czk: f64,
+    /// Reduced rate for idle minutes overriding the one of the tariff, `None` to use that.
+    idle_per_minute_czk: Option<f64>,
+    /// Show a price for each tariff, not just the selected one.
+    show_all_tariffs: bool,
 }
 
-impl Car4way {
-    pub fn name(&self) -> &'static str {
+impl ProviderImpl for Car4way {
+    fn name(&self) -> &'static str {
         "car4way"
     }
 
-    pub fn calculate(&self, input_data: TripInputData) -> CalculationResult {
+    fn id(&self) -> &'static str {
+        "car4way"
+    }
+
+    fn calculate(&self, input_data: TripInputData) -> Result<CalculationResult> {
         debug!("Car4way::calculate({input_data:?}) called");
-        let tariff =
-            TARIFFS.iter().find(|t| t.kind == self.tariff).expect("all tariffs should be loaded");
-        tariff.calculate(input_data, &self.car_types)
+        self.calculate_with(self.tariff, input_data)
+    }
+
+    /// Why the trip is not possible with the selected tariff, if it isn't.
+    fn unavailable_reason(&self, input_data: TripInputData) -> Option<String> {
+        if self.car_types.is_empty() {
+            return Some(format!("nelze: {NO_CAR_TYPES}"));
+        }
+        match tariffs_on(input_data.begin.date()).and_then(|version| version.tariff(self.tariff)) {
+            Ok(tariff) => tariff.limits.unavailable_reason(input_data),
+            Err(e) => Some(format!("nelze: {e:#}")),
+        }
+    }
+
+    fn branding(&self) -> Branding {
+        Branding { logo: Some(asset!("/assets/logos/car4way.svg")), color: Some("#00a7e1") }
+    }
+
+    fn supports_packages(&self) -> bool {
+        true
+    }
+
+    fn invoice_rounding(&self) -> InvoiceRounding {
+        INVOICE_ROUNDING
+    }
+
+    fn render_input(car4way: Signal<Self>, input_data: TripInputData) -> Element {
+        rsx! {
+            Car4wayInput { car4way }
+            Car4wayPriceListNote { input_data }
+            Car4wayCarTypes { car4way, input_data }
+            Car4wayBreakEven { car4way, input_data }
+            Car4wayTariffs { car4way, input_data }
+            Car4wayExtension { car4way, input_data }
+        }
+    }
+}
+
+impl Car4way {
+    fn calculate_with(
+        &self,
+        tariff_kind: TariffKind,
+        input_data: TripInputData,
+    ) -> Result<CalculationResult> {
+        let tariff = tariffs_on(input_data.begin.date())?.tariff(tariff_kind)?;
+        let mut result = tariff.calculate(input_data, &self.car_types, self.idle_per_minute_czk)?;
+        self.add_extras(input_data, &mut result);
+        Ok(result)
+    }
+
+    /// Add-ons billed on top of the tariff price.
+    fn add_extras(&self, input_data: TripInputData, result: &mut CalculationResult) {
+        self.insurance.apply(input_data, result);
+        input_data.return_location.apply_fee(self.out_of_zone_return_czk, result);
+    }
+
+    /// Finish `result` (with [extras](Car4way::add_extras())) like [`Provider::calculate()`] does,
+    /// so that prices in the details match the headline one.
+    ///
+    /// [`Provider::calculate()`]: crate::provider::Provider::calculate()
+    fn as_headline(input_data: TripInputData, mut result: CalculationResult) -> CalculationResult {
+        INVOICE_ROUNDING.apply(&mut result);
+        input_data.discount.apply(&mut result);
+        result
+    }
+
+    /// Invoiced result of the trip with the selected tariff for each of the selected car types,
+    /// cheapest (the one [`Car4way::calculate()`] picks) first.
+    fn car_type_results(
+        &self,
+        input_data: TripInputData,
+    ) -> Result<Vec<(CarType, CalculationResult)>> {
+        let tariff = tariffs_on(input_data.begin.date())?.tariff(self.tariff)?;
+        let results =
+            tariff.calculate_detailed(input_data, &self.car_types, self.idle_per_minute_czk)?;
+        let mut results: Vec<_> = results
+            .into_iter()
+            .map(|(car_type, mut result)| {
+                self.add_extras(input_data, &mut result);
+                (car_type, Self::as_headline(input_data, result))
+            })
+            .collect();
+        // Stable, the first of equally priced ones wins like in `Tariff::calculate()`.
+        results.sort_by(|(_, a), (_, b)| a.cmp_price(b));
+        Ok(results)
+    }
+
+    /// Names of packages of the selected tariff valid on `date` for `car_type`.
+    fn package_names(&self, date: Date, car_type: CarType) -> Vec<String> {
+        let Ok(tariff) = tariffs_on(date).and_then(|version| version.tariff(self.tariff)) else {
+            return vec![];
+        };
+        let packages = &tariff.per_cartype[car_type].packages;
+        packages.iter().map(|p| p.name.clone()).collect()
+    }
+
+    /// Additional price of extending an ongoing rental, so far `elapsed`, by `minutes` and `km`,
+    /// keeping the car type and package (by name) taken at its beginning.
+    fn extension_cost(
+        &self,
+        elapsed: TripInputData,
+        minutes: i64,
+        km: f64,
+        car_type: CarType,
+        package: Option<&str>,
+    ) -> Result<f64> {
+        let tariff = tariffs_on(elapsed.begin.date())?.tariff(self.tariff)?;
+        let per_car_tariff = &tariff.per_cartype[car_type];
+        let package = package
+            .map(|name| {
+                per_car_tariff
+                    .packages
+                    .iter()
+                    .find(|p| p.name == name)
+                    .with_context(|| format!("unknown package {name}"))
+            })
+            .transpose()?;
+        if !(0..=MAX_EXTENSION_MINUTES).contains(&minutes) {
+            bail!("extension by {minutes} minutes, not 0 to {MAX_EXTENSION_MINUTES}");
+        }
+        let end =
+            elapsed.end.checked_add(minutes.minutes()).context("extended end out of range")?;
+        let extended = TripInputData { end, km: elapsed.km + km, ..elapsed };
+
+        let price = |input_data| -> Result<f64> {
+            // Idle minutes describe a planned trip, mid-rental all the time is billed as is.
+            let mut result = tariff.calculate_for_package(
+                input_data,
+                car_type,
+                &per_car_tariff.per_minute,
+                package,
+                None,
+            )?;
+            self.add_extras(input_data, &mut result);
+            Ok(Self::as_headline(input_data, result).total_czk())
+        };
+        Ok(price(extended)? - price(elapsed)?)
+    }
+
+    /// Invoiced price of the trip with each tariff; `None` if the tariff doesn't allow the trip.
+    fn calculate_all_tariffs(
+        &self,
+        input_data: TripInputData,
+    ) -> Vec<(TariffKind, Option<Result<CalculationResult>>)> {
+        let Ok(version) = tariffs_on(input_data.begin.date()) else {
+            return vec![];
+        };
+        TariffKind::iter()
+            .map(|kind| {
+                let result = match version.tariff(kind) {
+                    Ok(tariff) => {
+                        tariff.limits.unavailable_reason(input_data).is_none().then(|| {
+                            let result = self.calculate_with(kind, input_data)?;
+                            Ok(Self::as_headline(input_data, result))
+                        })
+                    },
+                    Err(e) => Some(Err(e)),
+                };
+                (kind, result)
+            })
+            .collect()
+    }
+
+    /// The tariff with the lowest invoiced price for the trip.
+    fn cheapest_tariff(
+        results: &[(TariffKind, Option<Result<CalculationResult>>)],
+    ) -> Option<TariffKind> {
+        results
+            .iter()
+            .filter_map(|(kind, result)| Some((kind, result.as_ref()?.as_ref().ok()?)))
+            .min_by(|(_, a), (_, b)| a.cmp_price(b))
+            .map(|(kind, _)| *kind)
+    }
+
+    /// Pick the cheapest tariff for typical usage during the month that contains `month`.
+    fn recommend_tariff(&self, month: Date) -> Option<TariffKind> {
+        if self.car_types.is_empty() {
+            return None;
+        }
+        let version = tariffs_on(month).ok()?;
+        TariffKind::iter()
+            .filter(|kind| self.monthly_usage.business_customer || *kind != TariffKind::Business)
+            .filter_map(|kind| version.tariff(kind).ok())
+            .filter_map(|t| {
+                let result = t.calculate_month(&self.monthly_usage, month, &self.car_types);
+                result
+                    .inspect_err(|e| error!("simulating month for {:?}: {e:#}", t.kind))
+                    .ok()
+                    .map(|result| (t.kind, result))
+            })
+            .min_by(|(_, a), (_, b)| a.cmp_price(b))
+            .map(|(kind, _)| kind)
     }
 }
 
 impl Default for Car4way {
     fn default() -> Self {
-        Self { tariff: TariffKind::default(), car_types: CarType::iter().collect() }
+        Self {
+            tariff: TariffKind::default(),
+            car_types: CarType::iter().collect(),
+            monthly_usage: MonthlyUsage::default(),
+            insurance: Insurance::new(INSURANCE_PER_DAY_CZK),
+            out_of_zone_return_czk: OUT_OF_ZONE_RETURN_CZK,
+            idle_per_minute_czk: None,
+            show_all_tariffs: false,
+        }
+    }
+}
+
+/// Typical monthly usage used to recommend a tariff.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct MonthlyUsage {
+    trips: u32,
+    total_km: f64,
+    trip_minutes: i64,
+    /// Only companies are eligible for the Business tariff.
+    business_customer: bool,
+}
+
+impl MonthlyUsage {
+    /// Most trips a month the UI accepts, each of them is calculated for the recommendation.
+    const MAX_TRIPS: u32 = 200;
+    /// Longest typical trip the UI accepts, a week.
+    const MAX_TRIP_MINUTES: i64 = 7 * 24 * 60;
+
+    /// Spread the trips evenly across the month, each beginning at 10:00. Fails for a trip length
+    /// out of range.
+    fn simulated_trips(&self, month: Date) -> impl Iterator<Item = Result<TripInputData>> {
+        let days_in_month = i64::from(month.days_in_month());
+        let km = if self.trips > 0 { self.total_km / f64::from(self.trips) } else { 0.0 };
+        let usage = *self;
+
+        (0..i64::from(self.trips)).map(move |i| {
+            let day = month.first_of_month() + (i * days_in_month / i64::from(usage.trips)).days();
+            let begin = day.at(10, 0, 0, 0);
+            let minutes = usage.trip_minutes;
+            let end = Span::new()
+                .try_minutes(minutes)
+                .and_then(|span| begin.checked_add(span))
+                .with_context(|| format!("trip of {minutes} minutes"))?;
+            ensure!(begin <= end, "trip of {minutes} minutes");
+            Ok(TripInputData::from_times(km, begin, end))
+        })
+    }
+}
+
+impl Default for MonthlyUsage {
+    fn default() -> Self {
+        Self { trips: 4, total_km: 80.0, trip_minutes: 120, business_customer: false }
     }
 }
 
 #[component]
 pub fn Car4wayInput(car4way: Signal<Car4way>) -> Element {
+    let lang = use_lang();
     let name = car4way.read().name();
 
     let tariff_changed = move |evt: FormEvent| {
@@ -61,9 +352,63 @@ pub fn Car4wayInput(car4way: Signal<Car4way>) -> Element {
         }
     };
 
+    let trips_changed = move |evt: FormEvent| {
+        car4way.write().monthly_usage.trips = evt.parsed::<u32>()?.min(MonthlyUsage::MAX_TRIPS);
+        Ok(())
+    };
+    let total_km_changed = move |evt: FormEvent| {
+        car4way.write().monthly_usage.total_km = evt.parsed()?;
+        Ok(())
+    };
+    let trip_minutes_changed = move |evt: FormEvent| {
+        car4way.write().monthly_usage.trip_minutes =
+            evt.parsed::<i64>()?.clamp(0, MonthlyUsage::MAX_TRIP_MINUTES);
+        Ok(())
+    };
+    let business_customer_changed = move |evt: FormEvent| {
+        car4way.write().monthly_usage.business_customer = evt.checked();
+    };
+
+    let insurance_changed = move |evt: FormEvent| {
+        car4way.write().insurance.enabled = evt.checked();
+    };
+    let insurance_price_changed = move |evt: FormEvent| {
+        car4way.write().insurance.per_day_czk = evt.parsed()?;
+        Ok(())
+    };
+
+    let out_of_zone_return_changed = move |evt: FormEvent| {
+        car4way.write().out_of_zone_return_czk = evt.parsed()?;
+        Ok(())
+    };
+
+    let idle_per_minute_changed = move |evt: FormEvent| {
+        let value = evt.value();
+        car4way.write().idle_per_minute_czk =
+            if value.is_empty() { None } else { Some(value.parse()?) };
+        Ok(())
+    };
+
+    let show_all_tariffs_changed = move |evt: FormEvent| {
+        car4way.write().show_all_tariffs = evt.checked();
+    };
+
+    // Simulating a month with each tariff is costly, so it's done only while it's shown.
+    let mut monthly_usage_open = use_signal(|| false);
+    let recommended = use_memo(move || {
+        monthly_usage_open().then(|| car4way.read().recommend_tariff(now_in_prague().date()))
+    });
+
+    let insurance = car4way.read().insurance;
+    let monthly_usage = car4way.read().monthly_usage;
+    let recommended = match recommended() {
+        Some(Some(kind)) => kind.to_string(),
+        _ => "—".into(),
+    };
+
     rsx! {
         p {
-                label { for: "provider-{name}-tariff", "Tarif: " },
+                label { for: "provider-{name}-tariff", "{t(Key::Tariff, lang)}: " },
                 select { id: "provider-{name}-tariff",
                     onchange: tariff_changed,
                     for tariff_kind in TariffKind::iter() {
@@ -73,9 +418,16 @@ pub fn Car4wayInput(car4way: Signal<Car4way>) -> Element {
                         }
                     }
                 }
+                " ",
+                input { id: "provider-{name}-show-all-tariffs",
+                    r#type: "checkbox",
+                    checked: car4way.read().show_all_tariffs,
+                    onchange: show_all_tariffs_changed,
+                }
+                label { for: "provider-{name}-show-all-tariffs", " {t(Key::ShowAllTariffs, lang)}" },
         }
         p {
-                "Kategorie aut: ",
+                "{t(Key::CarTypes, lang)}: ",
                 for car_type in CarType::iter() {
                     input { id: "provider-{name}-cartype-{car_type}",
                         r#type: "checkbox",
@@ -85,11 +437,312 @@ pub fn Car4wayInput(car4way: Signal<Car4way>) -> Element {
                     label { for: "provider-{name}-cartype-{car_type}", "{car_type} " },
                 }
         }
+        p {
+            input { id: "provider-{name}-insurance",
+                r#type: "checkbox",
+                checked: insurance.enabled,
+                onchange: insurance_changed,
+            }
+            label { for: "provider-{name}-insurance", " {t(Key::InsuranceFor, lang)} " },
+            input { id: "provider-{name}-insurance-price",
+                r#type: "number",
+                value: insurance.per_day_czk,
+                onchange: insurance_price_changed,
+                min: 0,
+            },
+            " {t(Key::CzkPerDay, lang)}"
+        }
+        p {
+            label { for: "provider-{name}-out-of-zone", "{t(Key::OutOfZoneReturn, lang)}: " },
+            input { id: "provider-{name}-out-of-zone",
+                r#type: "number",
+                value: car4way.read().out_of_zone_return_czk,
+                onchange: out_of_zone_return_changed,
+                min: 0,
+            },
+            " Kč"
+        }
+        p {
+            label { for: "provider-{name}-idle-per-minute", "{t(Key::IdleRate, lang)}: " },
+            input { id: "provider-{name}-idle-per-minute",
+                r#type: "number",
+                placeholder: t(Key::LikeDriving, lang),
+                value: car4way.read().idle_per_minute_czk.map(|czk| czk.to_string()),
+                onchange: idle_per_minute_changed,
+                min: 0,
+                step: 0.01,
+            },
+            " {t(Key::CzkPerMinute, lang)}"
+        }
+        details {
+            ontoggle: move |_| monthly_usage_open.toggle(),
+            summary { {t(Key::MonthlyUsage, lang)} },
+            p { "{t(Key::RecommendedTariff, lang)}: {recommended}" },
+            p {
+                label { for: "provider-{name}-monthly-trips", "{t(Key::MonthlyTrips, lang)} " },
+                input { id: "provider-{name}-monthly-trips",
+                    r#type: "number",
+                    value: monthly_usage.trips,
+                    onchange: trips_changed,
+                    min: 0,
+                    max: MonthlyUsage::MAX_TRIPS,
+                },
+            },
+            p {
+                label { for: "provider-{name}-monthly-km", "{t(Key::MonthlyKm, lang)} " },
+                input { id: "provider-{name}-monthly-km",
+                    r#type: "number",
+                    value: monthly_usage.total_km,
+                    onchange: total_km_changed,
+                    min: 0,
+                },
+            },
+            p {
+                label { for: "provider-{name}-monthly-minutes", "{t(Key::TypicalTripMinutes, lang)} " },
+                input { id: "provider-{name}-monthly-minutes",
+                    r#type: "number",
+                    value: monthly_usage.trip_minutes,
+                    onchange: trip_minutes_changed,
+                    min: 0,
+                    max: MonthlyUsage::MAX_TRIP_MINUTES,
+                },
+            },
+            p {
+                input { id: "provider-{name}-business-customer",
+                    r#type: "checkbox",
+                    checked: monthly_usage.business_customer,
+                    onchange: business_customer_changed,
+                }
+                label { for: "provider-{name}-business-customer", " {t(Key::BusinessCustomer, lang)}" },
+            },
+        }
+    }
+}
+
+/// Warn when the trip begins before the oldest known price list.
+#[component]
+fn Car4wayPriceListNote(input_data: TripInputData) -> Element {
+    let date = input_data.begin.date();
+    // A loading error is shown as the reason Car4way is unavailable.
+    let Ok(versions) = TARIFFS.as_ref() else {
+        return rsx! {};
+    };
+    if TariffVersion::valid_on(versions, date).is_some() {
+        return rsx! {};
+    }
+    let latest = versions.last().expect("at least one price list is loaded");
+    rsx! {
+        p { class: "note",
+            "Ceník platný {date} neznáme, počítáno s ceníkem od {latest.valid_from}."
+        }
+    }
+}
+
+/// Why the car type was chosen: price of the trip with each of the selected ones.
+#[component]
+pub fn Car4wayCarTypes(car4way: Signal<Car4way>, input_data: TripInputData) -> Element {
+    let lang = use_lang();
+    let currency = use_currency();
+    if car4way.read().car_types.len() < 2 {
+        return rsx! {};
+    }
+    let results = match car4way.read().car_type_results(input_data) {
+        Ok(results) => results,
+        Err(e) => {
+            return rsx! { p { class: "unavailable", "{t(Key::CalculationError, lang)}: {e:#}" } };
+        },
+    };
+    let chosen = results.first().map(|(car_type, _)| car_type.name(lang));
+    let summary = results
+        .iter()
+        .map(|(car_type, result)| format!("{car_type} {}", currency.short(result.total_czk())))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    rsx! {
+        details {
+            summary { "{t(Key::CarTypes, lang)}: {summary}" },
+            table { class: "car-types",
+                tr {
+                    th { {t(Key::CarType, lang)} }
+                    th { {t(Key::Package, lang)} }
+                    th { {t(Key::Price, lang)} }
+                }
+                for (i, (car_type, result)) in results.iter().enumerate() {
+                    tr { class: if i == 0 { "chosen" },
+                        td { "{car_type.name(lang)}" }
+                        td { {result.package.as_deref().unwrap_or("—")} }
+                        td { "{currency.short(result.total_czk())}" }
+                    }
+                }
+            }
+            if let Some(chosen) = chosen {
+                p { "→ {t(Key::Chosen, lang)} {chosen}" }
+            }
+        }
+    }
+}
+
+/// From how long a trip beginning when `input_data` does packages pay off, for each car type.
+#[component]
+fn Car4wayBreakEven(car4way: Signal<Car4way>, input_data: TripInputData) -> Element {
+    let begin = input_data.begin;
+    let Ok(tariff) = tariffs_on(begin.date()).and_then(|version| version.tariff(car4way().tariff))
+    else {
+        return rsx! {};
+    };
+    let break_evens = car4way
+        .read()
+        .car_types
+        .iter()
+        .filter_map(|&car_type| {
+            let break_even = tariff.per_cartype[car_type].package_break_even(begin)?;
+            Some(format!("{} ({car_type})", format_duration(break_even.try_into().ok()?)))
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    if break_evens.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        p { class: "note", "výhodnější balíček od {break_evens}" }
+    }
+}
+
+/// Marginal price of keeping the car longer, the trip so far is taken from `input_data`.
+#[component]
+pub fn Car4wayExtension(car4way: Signal<Car4way>, input_data: TripInputData) -> Element {
+    let lang = use_lang();
+    let currency = use_currency();
+    let name = car4way.read().name();
+    let mut car_type = use_signal(|| CarType::Legend);
+    let mut package = use_signal(|| None::<String>);
+    let mut minutes = use_signal(|| 60);
+    let mut km = use_signal(|| 0.0);
+
+    let car_type_changed = move |evt: FormEvent| {
+        car_type.set(evt.parsed()?);
+        package.set(None);
+        Ok(())
+    };
+    let package_changed = move |evt: FormEvent| {
+        let value = evt.value();
+        package.set((!value.is_empty()).then_some(value));
+    };
+    let minutes_changed = move |evt: FormEvent| {
+        minutes.set(evt.parsed::<i64>()?.clamp(0, MAX_EXTENSION_MINUTES));
+        Ok(())
+    };
+    let km_changed = move |evt: FormEvent| {
+        km.set(evt.parsed()?);
+        Ok(())
+    };
+
+    let package_names = car4way.read().package_names(input_data.begin.date(), car_type());
+    let cost = car4way.read().extension_cost(
+        input_data,
+        minutes(),
+        km(),
+        car_type(),
+        package.read().as_deref(),
+    );
+
+    rsx! {
+        details {
+            summary { "Prodloužení probíhající jízdy" },
+            p { class: "note", "Dosavadní jízdu zadejte jako cestu výše." }
+            p {
+                select { id: "provider-{name}-extension-cartype",
+                    onchange: car_type_changed,
+                    for option_car_type in CarType::iter() {
+                        option { value: "{option_car_type}",
+                            selected: car_type() == option_car_type,
+                            "{option_car_type.name(lang)}"
+                        }
+                    }
+                }
+                select { id: "provider-{name}-extension-package",
+                    onchange: package_changed,
+                    option { value: "", "bez balíčku" }
+                    for package_name in package_names {
+                        option { value: "{package_name}",
+                            selected: package.read().as_deref() == Some(package_name.as_str()),
+                            "{package_name}"
+                        }
+                    }
+                }
+            }
+            p {
+                label { for: "provider-{name}-extension-minutes", "o minut " },
+                input { id: "provider-{name}-extension-minutes",
+                    r#type: "number",
+                    value: minutes(),
+                    onchange: minutes_changed,
+                    min: 0,
+                    max: MAX_EXTENSION_MINUTES,
+                },
+                label { for: "provider-{name}-extension-km", " a km " },
+                input { id: "provider-{name}-extension-km",
+                    r#type: "number",
+                    value: km(),
+                    onchange: km_changed,
+                    min: 0,
+                },
+            }
+            match cost {
+                Ok(cost) => rsx! { p { "Příplatek: {currency.short(cost)}" } },
+                Err(e) => rsx! { p { class: "unavailable", "chyba výpočtu: {e:#}" } },
+            }
+        }
+    }
+}
+
+/// Price of the trip with each tariff, if enabled by [`Car4way::show_all_tariffs`].
+#[component]
+pub fn Car4wayTariffs(car4way: Signal<Car4way>, input_data: TripInputData) -> Element {
+    let currency = use_currency();
+    if !car4way.read().show_all_tariffs {
+        return rsx! {};
+    }
+    let results = car4way.read().calculate_all_tariffs(input_data);
+    let cheapest = Car4way::cheapest_tariff(&results);
+
+    rsx! {
+        table { class: "tariffs",
+            for (kind, result) in results {
+                tr { key: "{kind}",
+                    class: if cheapest == Some(kind) { "cheapest" },
+                    td { "{kind}" }
+                    match result {
+                        None => rsx! { td { class: "unavailable", "nelze" } },
+                        Some(Ok(result)) => rsx! {
+                            td { "{currency.short(result.total_czk())}" }
+                            td { "{result.car_type}" }
+                        },
+                        Some(Err(e)) => rsx! { td { "chyba výpočtu: {e:#}" } },
+                    }
+                }
+            }
+        }
     }
 }
 
 #[derive(
-    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumIter, Display, EnumString,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Enum,
+    EnumIter,
+    Display,
+    EnumString,
+    Serialize,
+    Deserialize,
 )]
 enum TariffKind {
     #[default]
@@ -98,26 +751,93 @@ enum TariffKind {
     Business,
 }
 
-fn load_tariffs() -> Vec<Tariff> {
-    [(TariffKind::Basic, BASIC), (TariffKind::Active, ACTIVE), (TariffKind::Business, BUSINESS)]
-        .iter()
-        .map(|(name, data)| {
-            debug!("Loading {name:?}...");
-            load_tariff(*name, data)
-                .with_context(|| format!("loading {name:?} Car4way tariff"))
-                .expect("unit tested, should not fail")
+/// All tariffs of a price list. Each is parsed when first used, so that the selected one does not
+/// wait for the others.
+#[derive(Debug)]
+struct TariffVersion<'a> {
+    valid_from: Date,
+    data: EnumMap<TariffKind, &'a [u8]>,
+    /// Errors are formatted like the one of [`TARIFFS`].
+    tariffs: EnumMap<TariffKind, OnceLock<Result<Tariff, String>>>,
+}
+
+impl<'a> TariffVersion<'a> {
+    /// Price list of the basic, active and business tariff `data`, none of them parsed yet.
+    fn new(valid_from: Date, data: [&'a [u8]; 3]) -> Self {
+        let data = enum_map! {
+            TariffKind::Basic => data[0],
+            TariffKind::Active => data[1],
+            TariffKind::Business => data[2],
+        };
+        Self { valid_from, data, tariffs: EnumMap::default() }
+    }
+
+    /// The `kind` tariff, parsed now unless it already was.
+    fn tariff(&self, kind: TariffKind) -> Result<&Tariff> {
+        let valid_from = self.valid_from;
+        let tariff = self.tariffs[kind].get_or_init(|| {
+            debug!("Loading {kind:?} valid from {valid_from}...");
+            load_tariff(kind, self.data[kind])
+                .with_context(|| format!("loading {kind:?} Car4way tariff of {valid_from}"))
+                .map_err(|e| {
+                    error!("{e:#}");
+                    format!("{e:#}")
+                })
+        });
+        tariff.as_ref().map_err(|e| price_list_error(e))
+    }
+
+    /// The one of `versions` (oldest first) valid on `date`, `None` if it precedes all of them.
+    fn valid_on(versions: &[Self], date: Date) -> Option<&Self> {
+        versions.iter().rev().find(|version| version.valid_from <= date)
+    }
+}
+
+/// Error of using a price list (or a tariff of it) that failed to load with `e`.
+fn price_list_error(e: &str) -> anyhow::Error {
+    anyhow!("ceník se nepodařilo načíst: {e}")
+}
+
+/// Tariffs valid on `date`, the latest ones if no known price list was valid then.
+fn tariffs_on(date: Date) -> Result<&'static TariffVersion<'static>> {
+    let versions = TARIFFS.as_ref().map_err(|e| price_list_error(e))?;
+    let latest = || versions.last().expect("at least one price list is loaded");
+    Ok(TariffVersion::valid_on(versions, date).unwrap_or_else(latest))
+}
+
+/// Price lists in the form of [`TARIFF_DATA`], their tariffs are parsed lazily.
+fn load_tariffs<'a>(data: &[(&str, [&'a [u8]; 3])]) -> Result<Vec<TariffVersion<'a>>> {
+    if data.is_empty() {
+        bail!("no Car4way price list");
+    }
+    data.iter()
+        .map(|(valid_from, data)| {
+            let valid_from: Date = valid_from
+                .parse()
+                .with_context(|| format!("parsing Car4way price list date {valid_from}"))?;
+            Ok(TariffVersion::new(valid_from, *data))
         })
         .collect()
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 struct Tariff {
     kind: TariffKind,
     // NB(Matej): maybe better to transpose this?
     per_cartype: EnumMap<CarType, PerCarTariff>,
-    per_km_czk: f64,
+    /// Rates of kilometers beyond packages, ordered by [`KmTier::from_km`], the first from zero.
+    per_km: Vec<KmTier>,
     airport_enter_czk: f64,
     airport_leave_czk: f64,
+    /// Minimum total charge of a rental, if any.
+    min_total_czk: Option<f64>,
+    /// Reserving the car before the rental is free for this long.
+    free_reservation: SignedDuration,
+    /// Rate of reservation minutes beyond [`Tariff::free_reservation`], `None` if they are free.
+    reservation_per_minute_czk: Option<f64>,
+    limits: TripLimits,
+    /// Applied in order to each package candidate, see [`rules`].
+    rules: Vec<Arc<dyn PricingRule>>,
 }
 
 impl Tariff {
@@ -125,18 +845,80 @@ impl Tariff {
         &self,
         input_data: TripInputData,
         car_types: &BTreeSet<CarType>,
-    ) -> CalculationResult {
-        let results =
-            car_types.iter().map(|car_type| self.calculate_for_car(input_data, *car_type));
-        results.min().expect("car types are not empty")
+        idle_per_minute_czk: Option<f64>,
+    ) -> Result<CalculationResult> {
+        let results = self.calculate_detailed(input_data, car_types, idle_per_minute_czk)?;
+        results
+            .into_iter()
+            .map(|(_, result)| result)
+            .min_by(CalculationResult::cmp_price)
+            .context(NO_CAR_TYPES)
+    }
+
+    /// Result of each of `car_types`, in their order.
+    fn calculate_detailed(
+        &self,
+        input_data: TripInputData,
+        car_types: &BTreeSet<CarType>,
+        idle_per_minute_czk: Option<f64>,
+    ) -> Result<Vec<(CarType, CalculationResult)>> {
+        car_types
+            .iter()
+            .map(|&car_type| {
+                let result = self.calculate_for_car(input_data, car_type, idle_per_minute_czk)?;
+                Ok((car_type, result))
+            })
+            .collect()
+    }
+
+    /// Total price of a simulated month of usage.
+    fn calculate_month(
+        &self,
+        usage: &MonthlyUsage,
+        month: Date,
+        car_types: &BTreeSet<CarType>,
+    ) -> Result<CalculationResult> {
+        let mut result = CalculationResult::new(format!("{} měsíčně", self.kind));
+        for input_data in usage.simulated_trips(month) {
+            let input_data = input_data?;
+            let trip = self.calculate(input_data, car_types, None)?;
+            result
+                .add_component(trip.total_czk(), format!("{} {}", input_data.begin, trip.car_type));
+        }
+        Ok(result)
     }
 
-    fn calculate_for_car(&self, input_data: TripInputData, car_type: CarType) -> CalculationResult {
+    fn calculate_for_car(
+        &self,
+        input_data: TripInputData,
+        car_type: CarType,
+        idle_per_minute_czk: Option<f64>,
+    ) -> Result<CalculationResult> {
         let per_car_tariff = &self.per_cartype[car_type];
-        let results = per_car_tariff.packages.iter().map(Some).chain(Some(None)).map(|package| {
-            self.calculate_for_package(input_data, car_type, &per_car_tariff.per_minute, package)
-        });
-        results.min().expect("packages are not empty")
+        let packages: Vec<_> = per_car_tariff
+            .packages
+            .iter()
+            .filter(|_| input_data.packages)
+            .filter(|package| package.is_available(input_data.begin, input_data.end))
+            .collect();
+        let trip_duration = elapsed(input_data.begin, input_data.end).unsigned_abs();
+        let results = package_stacks(&packages, trip_duration)
+            .into_iter()
+            .chain(Some(vec![]))
+            .map(|stack| {
+                self.calculate_for_packages(
+                    input_data,
+                    car_type,
+                    &per_car_tariff.per_minute,
+                    &stack,
+                    idle_per_minute_czk,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(results
+            .into_iter()
+            .min_by(CalculationResult::cmp_price)
+            .expect("at least the no-package variant is present"))
     }
 
     fn calculate_for_package(
@@ -145,45 +927,115 @@ impl Tariff {
         car_type: CarType,
         per_minute: &[PerMinuteTariff],
         package: Option<&Package>,
-    ) -> CalculationResult {
-        let mut cursor = input_data.begin;
-        let mut remaining_km = input_data.km;
-        let mut components = vec![];
+        idle_per_minute_czk: Option<f64>,
+    ) -> Result<CalculationResult> {
+        let packages = package.as_slice();
+        self.calculate_for_packages(input_data, car_type, per_minute, packages, idle_per_minute_czk)
+    }
+
+    /// Bill the trip using `packages` one after another, and the rest at regular rates.
+    fn calculate_for_packages(
+        &self,
+        input_data: TripInputData,
+        car_type: CarType,
+        per_minute: &[PerMinuteTariff],
+        packages: &[&Package],
+        idle_per_minute_czk: Option<f64>,
+    ) -> Result<CalculationResult> {
+        let mut trip = TripState {
+            input_data,
+            per_minute,
+            packages,
+            idle_per_minute_czk: idle_per_minute_czk
+                .or(self.per_cartype[car_type].idle_per_minute_czk),
+            daily_cap_czk: self.per_cartype[car_type].daily_cap_czk,
+            cursor: input_data.begin,
+            remaining_km: input_data.rounding.round(input_data.km),
+        };
+        let mut result = CalculationResult::new(car_type.name(Lang::default()));
 
-        if let Some(package) = package {
-            // TODO(Matej): package time limitation!!!
-            cursor += package.duration;
-            remaining_km -= package.kilometers;
-            remaining_km = remaining_km.max(0.0);
+        for rule in &self.rules {
+            rule.apply(self, &mut trip, &mut result)?;
+        }
 
-            components.push(package.as_price_component());
+        // After everything else, discounts included.
+        if let Some(min_total_czk) = self.min_total_czk {
+            result.top_up_to(min_total_czk);
         }
 
-        while cursor < input_data.end {
-            let minute_tariff = per_minute
-                .iter()
-                .find(|minute_tariff| minute_tariff.contains_time(cursor.time()))
-                .expect("minute tariffs cover 24 hours");
+        Ok(result)
+    }
+}
 
-            let component = minute_tariff.advance(&mut cursor, input_data.end);
-            components.push(component);
-        }
+/// Combinations of `packages` worth trying for a trip lasting `trip_duration`: each one alone, then
+/// stacks of up to [`MAX_STACKED_PACKAGES`] of them (repetitions allowed) where each package is
+/// needed, i.e. the others don't cover the trip already.
+fn package_stacks<'a>(packages: &[&'a Package], trip_duration: Duration) -> Vec<Vec<&'a Package>> {
+    const MAX_STACKED_PACKAGES: usize = 3;
 
-        if remaining_km > 0.0 {
-            components.push(PriceComponent {
-                czk: remaining_km * self.per_km_czk,
-                name: format!("extra za {remaining_km} km"),
-            });
+    let mut stacks: Vec<Vec<&Package>> = packages.iter().map(|&package| vec![package]).collect();
+    let mut previous: Vec<(usize, Vec<&Package>)> =
+        packages.iter().enumerate().map(|(i, &package)| (i, vec![package])).collect();
+    for _ in 1..MAX_STACKED_PACKAGES {
+        let mut next = vec![];
+        for (last_index, stack) in &previous {
+            let stack_duration: Duration = stack.iter().map(|p| p.duration).sum();
+            // Extended only by packages not before the last one, so that each combination is
+            // generated once.
+            for (i, &package) in packages.iter().enumerate().skip(*last_index) {
+                let shortest = stack.iter().map(|p| p.duration).chain([package.duration]).min();
+                let shortest = shortest.expect("stack is not empty");
+                let without_shortest = stack_duration + package.duration - shortest;
+                if without_shortest < trip_duration {
+                    let mut extended = stack.clone();
+                    extended.push(package);
+                    next.push((i, extended));
+                }
+            }
         }
+        stacks.extend(next.iter().map(|(_, stack)| stack.clone()));
+        previous = next;
+    }
+    stacks
+}
 
-        // TODO(Matej): entering or leaving airport!
+/// Trips exceeding these are not possible without a special arrangement.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct TripLimits {
+    max_duration: Option<SignedDuration>,
+    max_km: Option<f64>,
+}
 
-        CalculationResult { car_type: car_type.name().into(), components }
+impl TripLimits {
+    fn unavailable_reason(&self, input_data: TripInputData) -> Option<String> {
+        if let Some(max_km) = self.max_km
+            && input_data.km > max_km
+        {
+            return Some(format!("nelze: trasa přesahuje maximum {max_km} km"));
+        }
+        if let Some(max_duration) = self.max_duration
+            && elapsed(input_data.begin, input_data.end) > max_duration
+        {
+            return Some(format!("nelze: pronájem přesahuje maximum {max_duration:#}"));
+        }
+        None
     }
 }
 
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Enum, EnumIter, Display, EnumString,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Enum,
+    EnumIter,
+    Display,
+    EnumString,
+    Serialize,
+    Deserialize,
 )]
 enum CarType {
     Legend,
@@ -192,11 +1044,14 @@ enum CarType {
 }
 
 impl CarType {
-    fn name(&self) -> &'static str {
-        match self {
-            CarType::Legend => "Legend (Fabia)",
-            CarType::Fancy => "Fancy (Scala, Karoq, Octavia, Caddy Van)",
-            CarType::Boss => "Boss (Superb, Kodiaq)",
+    fn name(&self, lang: Lang) -> &'static str {
+        match (self, lang) {
+            (CarType::Legend, Lang::Cs) => "Legend (Fabia)",
+            (CarType::Legend, Lang::En) => "Legend (Škoda Fabia)",
+            (CarType::Fancy, Lang::Cs) => "Fancy (Scala, Karoq, Octavia, Caddy Van)",
+            (CarType::Fancy, Lang::En) => "Fancy (Škoda Scala, Karoq, Octavia, VW Caddy Van)",
+            (CarType::Boss, Lang::Cs) => "Boss (Superb, Kodiaq)",
+            (CarType::Boss, Lang::En) => "Boss (Škoda Superb, Kodiaq)",
         }
     }
 }
@@ -205,44 +1060,115 @@ impl CarType {
 struct PerCarTariff {
     per_minute: Vec<PerMinuteTariff>,
     packages: Vec<Package>,
+    /// Most that minute billing (outside of packages) costs in any 24 hours, if capped.
+    daily_cap_czk: Option<f64>,
+    /// Rate of standing (parked) minutes, if lower than the regular one.
+    idle_per_minute_czk: Option<f64>,
+}
+
+impl PerCarTariff {
+    /// Shortest trip from `begin` for which a package (ignoring its kilometers) costs less than
+    /// billing its minutes, scanned minute by minute up to the longest package. `None` if minutes
+    /// are always cheaper.
+    fn package_break_even(&self, begin: DateTime) -> Option<SignedDuration> {
+        let longest = self.packages.iter().map(|package| package.duration).max()?;
+        let mut rolling_cap = self.daily_cap_czk.map(RollingCap::new);
+        let mut minutes_czk = 0.0;
+        for minute in 1..=longest.as_secs() as i64 / 60 {
+            let start = begin + (minute - 1).minutes();
+            let minute_tariff =
+                self.per_minute.iter().find(|minute_tariff| minute_tariff.applies_at(start))?;
+            let czk = minute_tariff.per_minute_czk;
+            minutes_czk += rolling_cap.as_mut().map_or(czk, |cap| cap.bill_minute(czk));
+
+            let end = begin + minute.minutes();
+            let package_czk = self
+                .packages
+                .iter()
+                .filter(|package| package.duration.as_secs() as i64 >= minute * 60)
+                .filter(|package| package.is_available(begin, end))
+                .map(|package| package.czk)
+                .reduce(f64::min);
+            if package_czk.is_some_and(|package_czk| package_czk < minutes_czk) {
+                return Some(SignedDuration::from_mins(minute));
+            }
+        }
+        None
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 struct PerMinuteTariff {
     start: Time,
     end: Time,
+    /// Days (of the minute billed) the tariff applies on. Where more tariffs apply, the one of
+    /// fewer days (e.g. a weekend one) wins.
+    weekdays: WeekdayRange,
     per_minute_czk: f64,
 }
 
 impl PerMinuteTariff {
     fn name(&self) -> String {
-        format!("minutový tarif {:02}-{:02}h", self.start.hour(), self.end.hour())
+        let name = format!("minutový tarif {:02}-{:02}h", self.start.hour(), self.end.hour());
+        if self.weekdays == WeekdayRange::ALL { name } else { format!("{name} {}", self.weekdays) }
     }
 
-    fn contains_time(&self, time: Time) -> bool {
-        if self.start < self.end {
-            self.start <= time && time < self.end
-        } else {
-            self.start <= time || time < self.end
-        }
+    fn applies_at(&self, datetime: DateTime) -> bool {
+        self.weekdays.contains(datetime.weekday())
+            && TimeWindow { start: self.start, end: self.end }.contains(datetime.time())
     }
 
-    fn advance(&self, cursor: &mut DateTime, trip_end: DateTime) -> PriceComponent {
-        let first_possibility = cursor.with().time(self.end).build().expect("can set time");
-        let tariff_end = if *cursor < first_possibility {
-            first_possibility
-        } else {
-            first_possibility + Duration::from_secs(24 * 60 * 60)
-        };
+    /// The nearest end of this tariff after `cursor`, i.e. the next calendar day for tariffs
+    /// wrapping over midnight (like the night one) when `cursor` is before midnight.
+    fn segment_end(&self, cursor: DateTime) -> DateTime {
+        let same_day = cursor.with().time(self.end).build().expect("can set time");
+        // Civil (calendar) day, not 24 hours: the trip times are wall-clock ones.
+        if cursor < same_day { same_day } else { same_day + 1.day() }
+    }
+
+    fn advance(
+        &self,
+        cursor: &mut DateTime,
+        trip_end: DateTime,
+        rounding: Rounding,
+        result: &mut CalculationResult,
+    ) -> i64 {
+        let end = min(self.segment_end(*cursor), trip_end);
+        let duration = elapsed(*cursor, end);
 
-        let end = min(tariff_end, trip_end);
-        let duration = end.duration_since(*cursor);
+        let minutes = rounding.minutes(duration);
 
         *cursor = end;
-        PriceComponent {
-            czk: duration.as_mins() as f64 * self.per_minute_czk,
-            name: format!("{} {} minut", self.name(), duration.as_mins()),
-        }
+        result.add_described_component(
+            minutes as f64 * self.per_minute_czk,
+            format!("{} {minutes} minut", self.name()),
+            per_minute_description(minutes, self.per_minute_czk),
+        );
+        minutes
+    }
+}
+
+/// Tooltip of a component billing `minutes` at `per_minute_czk`.
+fn per_minute_description(minutes: i64, per_minute_czk: f64) -> String {
+    format!("{minutes} minut × {per_minute_czk} Kč/min")
+}
+
+/// Rate of extra kilometers from a threshold on, until the next tier starts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct KmTier {
+    from_km: f64,
+    per_km_czk: f64,
+}
+
+impl KmTier {
+    /// Price of `km` extra kilometers, each of them billed by the tier it falls into.
+    fn czk(tiers: &[KmTier], km: f64) -> f64 {
+        let ends = tiers.iter().skip(1).map(|tier| tier.from_km).chain([f64::INFINITY]);
+        tiers
+            .iter()
+            .zip(ends)
+            .map(|(tier, end)| (km.min(end) - tier.from_km).max(0.0) * tier.per_km_czk)
+            .sum()
     }
 }
 
@@ -256,8 +1182,8 @@ struct Package {
 }
 
 impl Package {
-    fn as_price_component(&self) -> PriceComponent {
-        PriceComponent { czk: self.czk, name: self.name.clone() }
+    fn is_available(&self, begin: DateTime, end: DateTime) -> bool {
+        self.time_limitation.is_none_or(|limitation| limitation.contains(begin, end))
     }
 }
 
@@ -267,34 +1193,127 @@ struct TimeLimitation {
     to: WeekdayTime,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-struct WeekdayTime {
+impl TimeLimitation {
+    /// Whether the whole `[begin, end]` interval falls into a single occurrence of the window.
+    fn contains(&self, begin: DateTime, end: DateTime) -> bool {
+        let days_since_from = i64::from(begin.weekday().since(self.from.weekday));
+        let mut window_start = (begin.date() - days_since_from.days()).to_datetime(self.from.time);
+        if window_start > begin {
+            window_start -= 7.days();
+        }
+
+        let window_days = i64::from(self.to.weekday.since(self.from.weekday));
+        let mut window_end = (window_start.date() + window_days.days()).to_datetime(self.to.time);
+        if window_end <= window_start {
+            window_end += 7.days();
+        }
+
+        begin < window_end && end <= window_end
+    }
+
+    /// Length of a single occurrence of the window.
+    fn duration(&self) -> SignedDuration {
+        let days = i64::from(self.to.weekday.since(self.from.weekday));
+        let duration =
+            SignedDuration::from_hours(days * 24) + self.to.time.duration_since(self.from.time);
+        if duration <= SignedDuration::ZERO {
+            duration + SignedDuration::from_hours(7 * 24)
+        } else {
+            duration
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct WeekdayTime {
     weekday: Weekday,
     time: Time,
 }
 
+impl WeekdayTime {
+    /// Parse Czech weekday abbreviation like `Pá` and time like `16:00`.
+    fn parse(weekday: &str, time: &str) -> Result<Self> {
+        let weekday = parse_weekday(weekday)?;
+        let time = Time::strptime("%H:%M", time).with_context(|| format!("parsing time {time}"))?;
+        Ok(Self { weekday, time })
+    }
+}
+
+/// Czech abbreviations of weekdays from Monday, as used in the price list.
+const WEEKDAY_ABBREVIATIONS: [&str; 7] = ["Po", "Út", "St", "Čt", "Pá", "So", "Ne"];
+
+fn parse_weekday(abbreviation: &str) -> Result<Weekday> {
+    let offset = WEEKDAY_ABBREVIATIONS
+        .iter()
+        .position(|known| *known == abbreviation)
+        .with_context(|| format!("Unknown weekday {abbreviation}"))?;
+    Ok(Weekday::from_monday_zero_offset(offset as i8)?)
+}
+
+/// Days of the week from `first` to `last` (both inclusive), possibly wrapping over Sunday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WeekdayRange {
+    first: Weekday,
+    last: Weekday,
+}
+
+impl WeekdayRange {
+    const ALL: Self = Self { first: Weekday::Monday, last: Weekday::Sunday };
+
+    /// Parse a range like `So-Ne`.
+    fn parse(range: &str) -> Result<Self> {
+        let (first, last) = range.split_once('-').with_context(|| format!("no range {range}"))?;
+        Ok(Self { first: parse_weekday(first)?, last: parse_weekday(last)? })
+    }
+
+    fn contains(&self, weekday: Weekday) -> bool {
+        weekday.since(self.first) <= self.last.since(self.first)
+    }
+
+    fn len(&self) -> i8 {
+        self.last.since(self.first) + 1
+    }
+}
+
+impl fmt::Display for WeekdayRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let abbreviation =
+            |weekday: Weekday| WEEKDAY_ABBREVIATIONS[weekday.to_monday_zero_offset() as usize];
+        write!(f, "{}-{}", abbreviation(self.first), abbreviation(self.last))
+    }
+}
+
 fn load_tariff(kind: TariffKind, data: &[u8]) -> Result<Tariff> {
-    // Keep the times and regexes in sync!
-    const DAY_START: Time = Time::constant(6, 0, 0, 0);
-    const NIGHT_START: Time = Time::constant(20, 0, 0, 0);
-    const WEEKEND_START: Time = Time::constant(16, 0, 0, 0);
-    const WEEKEND_END: Time = Time::constant(10, 0, 0, 0);
-
-    static DAY_MINUTE_TARIFF_RE: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new("Denní: 6:00 - 20:00 Po-Ne").unwrap());
-    static NIGHT_MINUTE_TARIFF_RE: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new("Noční: 20:00 - 6:00 Po-Ne").unwrap());
+    static DAY_MINUTE_TARIFF_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new("^Denní: ([0-9]+):([0-9]+) - ([0-9]+):([0-9]+) (\\S+-\\S+)$").unwrap()
+    });
+    static NIGHT_MINUTE_TARIFF_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new("^Noční: ([0-9]+):([0-9]+) - ([0-9]+):([0-9]+) (\\S+-\\S+)$").unwrap()
+    });
     static HOUR_PACKAGE_RE: LazyLock<Regex> =
         LazyLock::new(|| Regex::new("([0-9]+) hodiny? \\+ ([0-9]+) km").unwrap());
     static DAY_PACKAGE_RE: LazyLock<Regex> =
         LazyLock::new(|| Regex::new("([0-9]+) dn[yí] \\+ ([0-9]+) km").unwrap());
+    static KM_TIER_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new("^Km nad rámec balíčků(?: od ([0-9]+) km)?$").unwrap());
+    static WEEKEND_PACKAGE_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new("^Víkend(?: \\((\\S+) ([0-9:]+) - (\\S+) ([0-9:]+)\\))? \\+ ([0-9]+) km$")
+            .unwrap()
+    });
 
-    let mut day_tariff = EnumMap::default();
-    let mut night_tariff = EnumMap::default();
+    let mut day_tariffs = EnumMap::default();
+    let mut night_tariffs = EnumMap::default();
     let mut packages: EnumMap<CarType, Vec<Package>> = EnumMap::default();
-    let mut per_km_czk = None;
+    let mut daily_cap_czk = EnumMap::default();
+    let mut idle_per_minute_czk = EnumMap::default();
+    let mut per_km = vec![];
     let mut airport_enter_czk = None;
     let mut airport_leave_czk = None;
+    let mut max_days = None;
+    let mut max_km = None;
+    let mut min_total_czk = None;
+    let mut free_reservation_minutes = None;
+    let mut reservation_per_minute_czk = None;
 
     let mut rdr = ReaderBuilder::new().delimiter(b'\t').trim(Trim::All).from_reader(data);
     // For debugging, one can use `for result in rdr.records() {`
@@ -302,73 +1321,162 @@ fn load_tariff(kind: TariffKind, data: &[u8]) -> Result<Tariff> {
         let row: TariffRow = result?;
         debug!("{row:?}");
 
-        if DAY_MINUTE_TARIFF_RE.is_match(&row.item) {
-            extract_minute_tariff(&row, &mut day_tariff, DAY_START, NIGHT_START)?;
-        } else if NIGHT_MINUTE_TARIFF_RE.is_match(&row.item) {
-            extract_minute_tariff(&row, &mut night_tariff, NIGHT_START, DAY_START)?;
+        if let Some(matches) = DAY_MINUTE_TARIFF_RE.captures(&row.item) {
+            extract_minute_tariff(&row, &mut day_tariffs, matches)?;
+        } else if let Some(matches) = NIGHT_MINUTE_TARIFF_RE.captures(&row.item) {
+            extract_minute_tariff(&row, &mut night_tariffs, matches)?;
         } else if row.item == "Výhodné balíčky" {
             // Pass.
         } else if let Some(matches) = HOUR_PACKAGE_RE.captures(&row.item) {
             extract_package(&row, &mut packages, matches, Duration::from_secs(60 * 60))?;
         } else if let Some(matches) = DAY_PACKAGE_RE.captures(&row.item) {
             extract_package(&row, &mut packages, matches, Duration::from_secs(24 * 60 * 60))?;
-        } else if row.item == "Víkend + 200 km" {
-            let time_limitation = Some(TimeLimitation {
-                from: WeekdayTime { weekday: Weekday::Friday, time: WEEKEND_START },
-                to: WeekdayTime { weekday: Weekday::Monday, time: WEEKEND_END },
-            });
-            extract_package_inner(
-                &row,
-                &mut packages,
-                Duration::from_secs((8 + 24 + 24 + 10) * 60 * 60),
-                200.0,
-                time_limitation,
-            )?;
-        } else if row.item == "Km nad rámec balíčků" {
-            per_km_czk = Some(row.only().context("expected exactly one value for per km price")?);
+        } else if let Some(matches) = WEEKEND_PACKAGE_RE.captures(&row.item) {
+            extract_weekend_package(&row, &mut packages, matches)?;
+        } else if row.item == "Denní strop" {
+            extract_per_car_values(&row, &mut daily_cap_czk)?;
+        } else if row.item == "Parkovné" {
+            extract_per_car_values(&row, &mut idle_per_minute_czk)?;
+        } else if let Some(matches) = KM_TIER_RE.captures(&row.item) {
+            let from_km = matches.get(1).map_or(Ok(0.0), |km| km.as_str().parse());
+            let from_km = from_km.context("parsing per km tier threshold")?;
+            let per_km_czk = row.only().context("expected exactly one value for per km price")?;
+            per_km.push(KmTier { from_km, per_km_czk });
         } else if row.item == "Letiště Praha - příjezd" {
             airport_enter_czk =
                 Some(row.only().context("expected single value for airport entry")?);
         } else if row.item == "Letiště Praha - výjezd" {
             airport_leave_czk =
                 Some(row.only().context("expected single value for airport leave")?);
+        } else if row.item == "Maximální délka pronájmu (dny)" {
+            max_days = Some(row.only().context("expected single value for max rental days")?);
+        } else if row.item == "Maximální nájezd (km)" {
+            max_km = Some(row.only().context("expected single value for max km")?);
+        } else if row.item == "Minimální cena pronájmu (Kč)" {
+            min_total_czk = Some(row.only().context("expected single value for min total price")?);
+        } else if row.item == "Rezervace zdarma (min)" {
+            free_reservation_minutes =
+                Some(row.only().context("expected single value for free reservation")?);
+        } else if row.item == "Rezervace (Kč/min)" {
+            reservation_per_minute_czk =
+                Some(row.only().context("expected single value for reservation price")?);
         } else {
             bail!("The item {:?} doesn't match any pattern.", row.item);
         }
     }
 
+    per_km.sort_by(|a, b| a.from_km.total_cmp(&b.from_km));
+    if per_km.first().is_none_or(|tier| tier.from_km != 0.0) {
+        bail!("per km price not parsed");
+    }
+
     Ok(Tariff {
         kind,
         per_cartype: enum_map! { car_type => {
             PerCarTariff {
-                per_minute: vec![
-                    day_tariff[car_type]
-                        .with_context(|| format!("no day minute tariff price for {car_type:?}"))?,
-                    night_tariff[car_type]
-                        .with_context(|| format!("no night minute tariff price for {car_type:?}"))?,
-                ],
+                per_minute: pair_minute_tariffs(
+                    car_type,
+                    &day_tariffs[car_type],
+                    &night_tariffs[car_type],
+                )?,
                 packages: mem::take(&mut packages[car_type]),
+                daily_cap_czk: daily_cap_czk[car_type],
+                idle_per_minute_czk: idle_per_minute_czk[car_type],
             }
         }},
-        per_km_czk: per_km_czk.context("per km price not parsed")?,
+        per_km,
         airport_enter_czk: airport_enter_czk.context("czk to enter airport not parsed")?,
         airport_leave_czk: airport_leave_czk.context("czk to leave airport not parsed")?,
+        min_total_czk,
+        free_reservation: SignedDuration::from_mins(free_reservation_minutes.unwrap_or(0.0) as i64),
+        reservation_per_minute_czk,
+        limits: TripLimits {
+            max_duration: max_days.map(|days| SignedDuration::from_hours((days * 24.0) as i64)),
+            max_km,
+        },
+        rules: rules::default_rules(),
     })
 }
 
+/// `matches` are the hours and minutes of the start and the end of the tariff window, then the
+/// weekdays it applies on.
 fn extract_minute_tariff(
     row: &TariffRow,
-    tariff: &mut EnumMap<CarType, Option<PerMinuteTariff>>,
-    start: Time,
-    end: Time,
+    tariffs: &mut EnumMap<CarType, Vec<PerMinuteTariff>>,
+    matches: Captures,
 ) -> Result<()> {
+    let time = |hour: usize| -> Result<Time> {
+        let (hour, minute) = (&matches[hour], &matches[hour + 1]);
+        Ok(Time::new(hour.parse()?, minute.parse()?, 0, 0)?)
+    };
+    let start = time(1).with_context(|| format!("parsing start of {}", row.item))?;
+    let end = time(3).with_context(|| format!("parsing end of {}", row.item))?;
+    let weekdays = WeekdayRange::parse(&matches[5])?;
+
     for (car_type, per_minute_czk) in
         [(CarType::Legend, row.legend), (CarType::Fancy, row.fancy), (CarType::Boss, row.boss)]
     {
         let Some(per_minute_czk) = per_minute_czk else {
             bail!("All columns should have valid price valid for item {}", row.item);
         };
-        tariff[car_type] = Some(PerMinuteTariff { start, end, per_minute_czk });
+        tariffs[car_type].push(PerMinuteTariff { start, end, weekdays, per_minute_czk });
+    }
+
+    Ok(())
+}
+
+/// Day and night tariffs of each weekday range together, which have to cover the whole day,
+/// the ones applying on fewer days first.
+fn pair_minute_tariffs(
+    car_type: CarType,
+    day_tariffs: &[PerMinuteTariff],
+    night_tariffs: &[PerMinuteTariff],
+) -> Result<Vec<PerMinuteTariff>> {
+    if day_tariffs.is_empty() {
+        bail!("no day minute tariff price for {car_type:?}");
+    }
+    if night_tariffs.len() != day_tariffs.len() {
+        bail!(
+            "{} day but {} night minute tariffs for {car_type:?}",
+            day_tariffs.len(),
+            night_tariffs.len()
+        );
+    }
+
+    let mut paired = vec![];
+    for day in day_tariffs {
+        let night = night_tariffs
+            .iter()
+            .find(|night| night.weekdays == day.weekdays)
+            .with_context(|| format!("no night minute tariff {} for {car_type:?}", day.weekdays))?;
+        if day.end != night.start || night.end != day.start {
+            bail!("{} and {} do not cover the whole day", day.name(), night.name());
+        }
+        paired.extend([*day, *night]);
+    }
+    let uncovered = Weekday::Monday
+        .cycle_forward()
+        .take(7)
+        .find(|weekday| !day_tariffs.iter().any(|day| day.weekdays.contains(*weekday)));
+    if let Some(weekday) = uncovered {
+        bail!("no minute tariff for {weekday:?}");
+    }
+
+    paired.sort_by_key(|tariff| tariff.weekdays.len());
+    Ok(paired)
+}
+
+fn extract_per_car_values(
+    row: &TariffRow,
+    values: &mut EnumMap<CarType, Option<f64>>,
+) -> Result<()> {
+    for (car_type, value) in
+        [(CarType::Legend, row.legend), (CarType::Fancy, row.fancy), (CarType::Boss, row.boss)]
+    {
+        let Some(value) = value else {
+            bail!("All columns should have valid price valid for item {}", row.item);
+        };
+        values[car_type] = Some(value);
     }
 
     Ok(())
@@ -386,6 +1494,11 @@ fn extract_package(
         .as_str()
         .parse()
         .context("parsing duration as integer")?;
+    // It would bill its price and kilometers without covering any time. Such "km vouchers" are not
+    // offered by Car4way, so this is most probably a typo in the data.
+    if duration == 0 {
+        bail!("Package {} has zero duration", row.item);
+    }
     let duration = duration_unit * duration;
 
     let kilometers: f64 = matches
@@ -398,6 +1511,36 @@ fn extract_package(
     extract_package_inner(row, packages, duration, kilometers, None)
 }
 
+/// Weekend package, optionally with its own window like `Víkend (Pá 12:00 - Po 8:00) + 200 km`.
+fn extract_weekend_package(
+    row: &TariffRow,
+    packages: &mut EnumMap<CarType, Vec<Package>>,
+    matches: Captures,
+) -> Result<()> {
+    /// The Car4way weekend, used when the row doesn't specify the window.
+    const DEFAULT_WEEKEND: TimeLimitation = TimeLimitation {
+        from: WeekdayTime { weekday: Weekday::Friday, time: Time::constant(16, 0, 0, 0) },
+        to: WeekdayTime { weekday: Weekday::Monday, time: Time::constant(10, 0, 0, 0) },
+    };
+
+    let time_limitation = match (matches.get(1), matches.get(2), matches.get(3), matches.get(4)) {
+        (Some(from_day), Some(from_time), Some(to_day), Some(to_time)) => TimeLimitation {
+            from: WeekdayTime::parse(from_day.as_str(), from_time.as_str())?,
+            to: WeekdayTime::parse(to_day.as_str(), to_time.as_str())?,
+        },
+        _ => DEFAULT_WEEKEND,
+    };
+    let kilometers: f64 = matches
+        .get(5)
+        .expect("has kilometers match")
+        .as_str()
+        .parse()
+        .context("parsing kilometers as float")?;
+    let duration = time_limitation.duration().try_into().context("weekend duration")?;
+
+    extract_package_inner(row, packages, duration, kilometers, Some(time_limitation))
+}
+
 fn extract_package_inner(
     row: &TariffRow,
     packages: &mut EnumMap<CarType, Vec<Package>>,
@@ -445,7 +1588,9 @@ impl TariffRow {
     }
 }
 
-fn deserialize_decimal_comma<'de, D: Deserializer<'de>>(des: D) -> Result<Option<f64>, D::Error> {
+pub(super) fn deserialize_decimal_comma<'de, D: Deserializer<'de>>(
+    des: D,
+) -> Result<Option<f64>, D::Error> {
     let string = String::deserialize(des)?;
 
     if string.is_empty() {
@@ -463,10 +1608,1112 @@ fn deserialize_decimal_comma<'de, D: Deserializer<'de>>(des: D) -> Result<Option
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::provider::{
+        Discount, DiscountKind, Provider, ReturnLocation, tests::with_signals, walk::Walk,
+    };
+    use jiff::civil::date;
     use test_log::test;
 
     #[test]
     fn test_load_tariffs() {
-        dbg!(load_tariffs());
+        for version in load_tariffs(&TARIFF_DATA).unwrap() {
+            for kind in TariffKind::iter() {
+                dbg!(version.tariff(kind).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_tariffs_parsed_on_demand() {
+        let versions = load_tariffs(&TARIFF_DATA).unwrap();
+        let version = versions.last().unwrap();
+        let parsed = || TariffKind::iter().filter(|kind| version.tariffs[*kind].get().is_some());
+        assert_eq!(parsed().count(), 0);
+
+        version.tariff(TariffKind::Basic).unwrap();
+        assert_eq!(parsed().collect::<Vec<_>>(), [TariffKind::Basic]);
+        // Switching the tariff parses just the newly selected one, and just once.
+        let active = version.tariff(TariffKind::Active).unwrap();
+        assert_eq!(parsed().collect::<Vec<_>>(), [TariffKind::Basic, TariffKind::Active]);
+        assert!(std::ptr::eq(active, version.tariff(TariffKind::Active).unwrap()));
+    }
+
+    #[test]
+    fn test_malformed_tariff_reported() {
+        let malformed = [ACTIVE, "Sleva pro věrné \t\t10 \t\n".as_bytes()].concat();
+        let versions = load_tariffs(&[("2025-05-01", [BASIC, &malformed, BUSINESS])]).unwrap();
+        let error = versions[0].tariff(TariffKind::Active).unwrap_err();
+        assert_eq!(
+            format!("{error:#}"),
+            "ceník se nepodařilo načíst: loading Active Car4way tariff of 2025-05-01: The item \
+             \"Sleva pro věrné\" doesn't match any pattern."
+        );
+        assert!(versions[0].tariff(TariffKind::Basic).is_ok(), "other tariffs stay usable");
+
+        let error = load_tariffs(&[("1. 5. 2025", [BASIC, ACTIVE, BUSINESS])]).unwrap_err();
+        assert_eq!(error.to_string(), "parsing Car4way price list date 1. 5. 2025");
+        assert_eq!(load_tariffs(&[]).unwrap_err().to_string(), "no Car4way price list");
+    }
+
+    /// Guards edits of the TSV files, naming what is wrong before the app makes Car4way unavailable.
+    #[test]
+    fn test_tariff_data_invariants() {
+        for (valid_from, data) in TARIFF_DATA {
+            for (kind, data) in TariffKind::iter().zip(data) {
+                let tariff = match load_tariff(kind, data) {
+                    Ok(tariff) => tariff,
+                    Err(e) => panic!("{kind:?} tariff of {valid_from} does not load: {e:#}"),
+                };
+                let context = format!("{kind:?} tariff of {valid_from}");
+
+                for (car_type, per_car) in &tariff.per_cartype {
+                    // A week from Monday; the first applying tariff is the narrowest one.
+                    let monday = date(2025, 6, 2).at(0, 0, 0, 0);
+                    for minute in 0..7 * 24 * 60 {
+                        let datetime = monday + SignedDuration::from_mins(minute);
+                        let Some(first) =
+                            per_car.per_minute.iter().find(|t| t.applies_at(datetime))
+                        else {
+                            panic!("{context}: {car_type:?} at {datetime} has no minute tariff");
+                        };
+                        let covering = (per_car.per_minute.iter())
+                            .filter(|t| t.weekdays == first.weekdays && t.applies_at(datetime))
+                            .count();
+                        assert_eq!(
+                            covering, 1,
+                            "{context}: {car_type:?} at {datetime} covered by {covering} minute \
+                             tariffs"
+                        );
+                    }
+                    for minute_tariff in &per_car.per_minute {
+                        let name = minute_tariff.name();
+                        assert!(
+                            minute_tariff.per_minute_czk > 0.0,
+                            "{context}: {car_type:?} {name}"
+                        );
+                    }
+                }
+                assert!(!tariff.per_km.is_empty(), "{context}: no per km price");
+                for tier in &tariff.per_km {
+                    assert!(tier.per_km_czk > 0.0, "{context}: per km price {tier:?}");
+                }
+                assert!(tariff.airport_enter_czk > 0.0, "{context}: airport entry fee");
+                assert!(tariff.airport_leave_czk > 0.0, "{context}: airport leave fee");
+            }
+        }
+    }
+
+    #[test]
+    fn test_tariffs_of_every_kind() {
+        for version in TARIFFS.as_ref().unwrap() {
+            for kind in TariffKind::iter() {
+                assert_eq!(version.tariff(kind).unwrap().kind, kind);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tariff_version_by_trip_date() {
+        let newer = String::from_utf8(BASIC.to_vec()).unwrap().replace("6,99", "7,49");
+        let newer = newer.as_bytes();
+        let versions: Vec<_> = [("2025-05-01", BASIC), ("2025-09-01", newer)]
+            .into_iter()
+            .map(|(valid_from, data)| TariffVersion::new(valid_from.parse().unwrap(), [data; 3]))
+            .collect();
+        let total = |begin: DateTime| {
+            let input_data = TripInputData::from_times(0.0, begin, begin + 1.hour());
+            let version = TariffVersion::valid_on(&versions, begin.date()).unwrap();
+            minutes_only(version.tariff(TariffKind::Basic).unwrap(), input_data).total_czk()
+        };
+
+        assert_eq!(total(date(2025, 8, 31).at(10, 0, 0, 0)), 60.0 * 6.99);
+        assert_eq!(total(date(2025, 9, 1).at(10, 0, 0, 0)), 60.0 * 7.49);
+        assert!(TariffVersion::valid_on(&versions, date(2025, 4, 30)).is_none());
+
+        // Without a known price list, the latest one is used.
+        let latest = TARIFFS.as_ref().unwrap().last().unwrap();
+        assert!(std::ptr::eq(tariffs_on(date(2020, 1, 1)).unwrap(), latest));
+        assert!(std::ptr::eq(tariffs_on(date(2030, 1, 1)).unwrap(), latest));
+    }
+
+    #[test]
+    fn test_km_tiers() {
+        assert_eq!(basic_tariff().per_km, [KmTier { from_km: 0.0, per_km_czk: 9.49 }]);
+
+        let data = String::from_utf8(BASIC.to_vec()).unwrap()
+            + "Km nad rámec balíčků od 100 km \t\t 4,00 \t\n";
+        let tariff = load_tariff(TariffKind::Basic, data.as_bytes()).unwrap();
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let extra_km_czk = |km: f64| {
+            let input_data = TripInputData::from_times(km, begin, begin);
+            minutes_only(&tariff, input_data).total_czk()
+        };
+
+        assert_eq!(extra_km_czk(30.0), 30.0 * 9.49);
+        assert_eq!(extra_km_czk(100.0), 100.0 * 9.49);
+        assert_eq!(extra_km_czk(150.0), 100.0 * 9.49 + 50.0 * 4.0);
+
+        let cheaper_first = [
+            KmTier { from_km: 0.0, per_km_czk: 5.0 },
+            KmTier { from_km: 10.0, per_km_czk: 3.0 },
+            KmTier { from_km: 20.0, per_km_czk: 1.0 },
+        ];
+        assert_eq!(KmTier::czk(&cheaper_first, 15.0), 10.0 * 5.0 + 5.0 * 3.0);
+        assert_eq!(KmTier::czk(&cheaper_first, 25.0), 10.0 * 5.0 + 10.0 * 3.0 + 5.0);
+
+        let only_tier = data.replace("Km nad rámec balíčků \t", "Km nad rámec balíčků od 5 km \t");
+        let error = load_tariff(TariffKind::Basic, only_tier.as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "per km price not parsed");
+    }
+
+    fn basic_tariff() -> &'static Tariff {
+        TARIFFS.as_ref().unwrap().last().unwrap().tariff(TariffKind::Basic).unwrap()
+    }
+
+    fn minutes_only(tariff: &Tariff, input_data: TripInputData) -> CalculationResult {
+        let per_minute = &tariff.per_cartype[CarType::Legend].per_minute;
+        tariff.calculate_for_package(input_data, CarType::Legend, per_minute, None, None).unwrap()
+    }
+
+    #[test]
+    fn test_minute_tariff_window_from_data() {
+        let data = String::from_utf8(BASIC.to_vec())
+            .unwrap()
+            .replace("Denní: 6:00 - 20:00", "Denní: 5:00 - 21:00")
+            .replace("Noční: 20:00 - 6:00", "Noční: 21:00 - 5:00");
+        let tariff = load_tariff(TariffKind::Basic, data.as_bytes()).unwrap();
+        let [day, night] = tariff.per_cartype[CarType::Boss].per_minute[..] else {
+            panic!("expected day and night tariffs");
+        };
+        assert_eq!((day.start, day.end), (Time::constant(5, 0, 0, 0), Time::constant(21, 0, 0, 0)));
+        assert_eq!((night.start, night.end), (day.end, day.start));
+        assert_eq!(day.name(), "minutový tarif 05-21h");
+
+        let begin = date(2025, 6, 3).at(20, 0, 0, 0);
+        let input_data = TripInputData::from_times(0.0, begin, begin + 90.minutes());
+        let names: Vec<_> =
+            minutes_only(&tariff, input_data).components.into_iter().map(|c| c.name).collect();
+        assert_eq!(names, ["minutový tarif 05-21h 60 minut", "minutový tarif 21-05h 30 minut"]);
+
+        let gap = data.replace("Noční: 21:00 - 5:00", "Noční: 22:00 - 5:00");
+        let error = load_tariff(TariffKind::Basic, gap.as_bytes()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "minutový tarif 05-21h and minutový tarif 22-05h do not cover the whole day"
+        );
+        let invalid = data.replace("Denní: 5:00", "Denní: 25:00");
+        assert!(load_tariff(TariffKind::Basic, invalid.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_weekend_minute_tariffs() {
+        let mut lines: Vec<_> = str::from_utf8(BASIC).unwrap().lines().collect();
+        lines.splice(
+            3..3,
+            [
+                "Denní: 8:00 - 20:00 So-Ne\t4,99\t5,99\t6,99",
+                "Noční: 20:00 - 8:00 So-Ne\t3,99\t4,99\t5,99",
+            ],
+        );
+        let tariff = load_tariff(TariffKind::Basic, lines.join("\n").as_bytes()).unwrap();
+        let names_czk = |begin: DateTime, minutes: i64| {
+            let input_data = TripInputData::from_times(0.0, begin, begin + minutes.minutes());
+            let result = minutes_only(&tariff, input_data);
+            result.components.into_iter().map(|c| (c.name, c.czk)).collect::<Vec<_>>()
+        };
+
+        let saturday = date(2025, 6, 7);
+        assert_eq!(
+            names_czk(saturday.at(7, 0, 0, 0), 120),
+            [
+                ("minutový tarif 20-08h So-Ne 60 minut".to_string(), 60.0 * 3.99),
+                ("minutový tarif 08-20h So-Ne 60 minut".into(), 60.0 * 4.99),
+            ]
+        );
+        let tuesday = date(2025, 6, 3);
+        assert_eq!(
+            names_czk(tuesday.at(7, 0, 0, 0), 60),
+            [("minutový tarif 06-20h 60 minut".to_string(), 60.0 * 6.99)]
+        );
+        // Friday night switches to the weekend one at midnight.
+        assert_eq!(
+            names_czk(date(2025, 6, 6).at(23, 0, 0, 0), 120),
+            [
+                ("minutový tarif 20-06h 60 minut".to_string(), 60.0 * 7.99),
+                ("minutový tarif 20-08h So-Ne 60 minut".into(), 60.0 * 3.99),
+            ]
+        );
+
+        let unpaired = lines.iter().filter(|line| !line.starts_with("Noční: 20:00 - 8:00"));
+        let unpaired = unpaired.copied().collect::<Vec<_>>().join("\n");
+        let error = load_tariff(TariffKind::Basic, unpaired.as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "2 day but 1 night minute tariffs for Legend");
+        let weekend_only = lines.iter().filter(|line| !line.contains("Po-Ne"));
+        let weekend_only = weekend_only.copied().collect::<Vec<_>>().join("\n");
+        let error = load_tariff(TariffKind::Basic, weekend_only.as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "no minute tariff for Monday");
+    }
+
+    #[test]
+    fn test_trip_beginning_at_day_start_bills_day_first() {
+        let begin = date(2025, 6, 3).at(6, 0, 0, 0);
+        let input_data = TripInputData::from_times(0.0, begin, begin + 1.hour());
+
+        let result = minutes_only(basic_tariff(), input_data);
+        assert_eq!(result.components.len(), 1);
+        assert_eq!(result.components[0].name, "minutový tarif 06-20h 60 minut");
+        assert_eq!(result.components[0].czk, 60.0 * 6.99);
+    }
+
+    #[test]
+    fn test_trip_beginning_at_night_start_bills_night_first() {
+        let begin = date(2025, 6, 3).at(20, 0, 0, 0);
+        let input_data = TripInputData::from_times(0.0, begin, begin + 1.hour());
+
+        let result = minutes_only(basic_tariff(), input_data);
+        assert_eq!(result.components.len(), 1);
+        assert_eq!(result.components[0].name, "minutový tarif 20-06h 60 minut");
+        assert_eq!(result.components[0].czk, 60.0 * 7.99);
+    }
+
+    #[test]
+    fn test_trip_ending_at_boundary_does_not_bill_next_segment() {
+        let begin = date(2025, 6, 3).at(19, 0, 0, 0);
+        let input_data = TripInputData::from_times(0.0, begin, begin + 1.hour());
+
+        let result = minutes_only(basic_tariff(), input_data);
+        assert_eq!(result.components.len(), 1);
+        assert_eq!(result.components[0].name, "minutový tarif 06-20h 60 minut");
+    }
+
+    #[test]
+    fn test_night_tariff_ends_next_day() {
+        let per_minute = &basic_tariff().per_cartype[CarType::Legend].per_minute;
+        let night = per_minute.iter().find(|t| t.name() == "minutový tarif 20-06h").unwrap();
+        let evening = date(2025, 6, 3);
+        let next_morning = date(2025, 6, 4).at(6, 0, 0, 0);
+
+        assert_eq!(night.segment_end(evening.at(22, 0, 0, 0)), next_morning);
+        assert_eq!(night.segment_end(evening.at(23, 59, 0, 0)), next_morning);
+        assert_eq!(night.segment_end(date(2025, 6, 4).at(0, 0, 0, 0)), next_morning);
+        assert_eq!(night.segment_end(date(2025, 6, 4).at(5, 0, 0, 0)), next_morning);
+
+        // End of month and year, too.
+        let new_year = date(2025, 12, 31).at(22, 0, 0, 0);
+        assert_eq!(night.segment_end(new_year), date(2026, 1, 1).at(6, 0, 0, 0));
+
+        let input_data = TripInputData::from_times(
+            0.0,
+            evening.at(22, 0, 0, 0),
+            date(2025, 6, 4).at(5, 0, 0, 0),
+        );
+        let result = minutes_only(basic_tariff(), input_data);
+        let components: Vec<_> = result.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(components, ["minutový tarif 20-06h 420 minut"]);
+    }
+
+    #[test]
+    fn test_minutes_across_dst_transitions() {
+        let billed = |begin: DateTime, end: DateTime| {
+            let result = minutes_only(basic_tariff(), TripInputData::from_times(0.0, begin, end));
+            result.components.iter().map(|c| c.name.clone()).collect::<Vec<_>>()
+        };
+
+        // Clocks jump from 2:00 to 3:00 on 2025-03-30, 1:00 to 4:00 is just 2 hours.
+        let spring = date(2025, 3, 30);
+        assert_eq!(
+            billed(spring.at(1, 0, 0, 0), spring.at(4, 0, 0, 0)),
+            ["minutový tarif 20-06h 120 minut"]
+        );
+        // Clocks fall from 3:00 back to 2:00 on 2025-10-26, the night is an hour longer.
+        let autumn = date(2025, 10, 26);
+        assert_eq!(
+            billed(autumn.at(1, 0, 0, 0), autumn.at(4, 0, 0, 0)),
+            ["minutový tarif 20-06h 240 minut"]
+        );
+        assert_eq!(
+            billed(date(2025, 10, 25).at(19, 0, 0, 0), autumn.at(7, 0, 0, 0)),
+            [
+                "minutový tarif 06-20h 60 minut",
+                "minutový tarif 20-06h 660 minut",
+                "minutový tarif 06-20h 60 minut",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_package_longer_than_trip() {
+        let tariff = basic_tariff();
+        let per_car_tariff = &tariff.per_cartype[CarType::Legend];
+        let package =
+            per_car_tariff.packages.iter().find(|p| p.name == "2 hodiny + 10 km").unwrap();
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+
+        let input_data = TripInputData::from_times(5.0, begin, begin + 20.minutes());
+        let result = tariff
+            .calculate_for_package(
+                input_data,
+                CarType::Legend,
+                &per_car_tariff.per_minute,
+                Some(package),
+                None,
+            )
+            .unwrap();
+        assert_eq!(result.car_type, "Legend (Fabia)");
+        assert_eq!(result.package.as_deref(), Some("2 hodiny + 10 km"));
+        assert_eq!(result.components.len(), 1, "no minute components expected");
+        assert_eq!(result.total_czk(), 249.0);
+
+        let input_data = TripInputData { km: 15.0, ..input_data };
+        let result = tariff
+            .calculate_for_package(
+                input_data,
+                CarType::Legend,
+                &per_car_tariff.per_minute,
+                Some(package),
+                None,
+            )
+            .unwrap();
+        let names: Vec<_> = result.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["2 hodiny + 10 km", "extra za 5 km"]);
+        assert_eq!(result.total_czk(), 249.0 + 5.0 * 9.49);
+    }
+
+    #[test]
+    fn test_minutes_after_day_package() {
+        let tariff = basic_tariff();
+        let per_car_tariff = &tariff.per_cartype[CarType::Legend];
+        let package =
+            per_car_tariff.packages.iter().find(|p| p.name == "24 hodin + 20 km").unwrap();
+        let billed = |begin: DateTime, end: DateTime| {
+            let input_data = TripInputData::from_times(0.0, begin, end);
+            let result = tariff
+                .calculate_for_package(
+                    input_data,
+                    CarType::Legend,
+                    &per_car_tariff.per_minute,
+                    Some(package),
+                    None,
+                )
+                .unwrap();
+            result.components.into_iter().map(|c| (c.name, c.czk)).collect::<Vec<_>>()
+        };
+        let package_component = ("24 hodin + 20 km".to_string(), 699.0);
+
+        // Starting at night, the overflow is at night again the next day.
+        let begin = date(2025, 6, 3).at(22, 0, 0, 0);
+        assert_eq!(
+            billed(begin, begin + 1.day() + 90.minutes()),
+            [package_component.clone(), ("minutový tarif 20-06h 90 minut".into(), 90.0 * 7.99)]
+        );
+        // The overflow crosses from the day tariff to the night one at 20:00.
+        let begin = date(2025, 6, 3).at(19, 30, 0, 0);
+        assert_eq!(
+            billed(begin, begin + 1.day() + 1.hour()),
+            [
+                package_component.clone(),
+                ("minutový tarif 06-20h 30 minut".into(), 30.0 * 6.99),
+                ("minutový tarif 20-06h 30 minut".into(), 30.0 * 7.99),
+            ]
+        );
+        // 24 real hours from 19:00 end at 18:00 the day clocks fall back, not at 19:00.
+        let begin = date(2025, 10, 25).at(19, 0, 0, 0);
+        assert_eq!(
+            billed(begin, date(2025, 10, 26).at(19, 30, 0, 0)),
+            [package_component.clone(), ("minutový tarif 06-20h 90 minut".into(), 90.0 * 6.99),]
+        );
+        // And at 20:00 the day clocks spring forward, which already is the night tariff.
+        let begin = date(2025, 3, 29).at(19, 0, 0, 0);
+        assert_eq!(
+            billed(begin, date(2025, 3, 30).at(20, 30, 0, 0)),
+            [package_component, ("minutový tarif 20-06h 30 minut".into(), 30.0 * 7.99)]
+        );
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let end = begin + 30.minutes() + 30.seconds();
+        let pessimistic = TripInputData::from_times(3.4, begin, end);
+        let optimistic = TripInputData { rounding: Rounding::Optimistic, ..pessimistic };
+
+        let names = |result: CalculationResult| -> Vec<_> {
+            result.components.into_iter().map(|c| c.name).collect()
+        };
+        assert_eq!(
+            names(minutes_only(basic_tariff(), pessimistic)),
+            ["minutový tarif 06-20h 31 minut", "extra za 4 km"]
+        );
+        assert_eq!(
+            names(minutes_only(basic_tariff(), optimistic)),
+            ["minutový tarif 06-20h 30 minut", "extra za 3 km"]
+        );
+        assert!(
+            minutes_only(basic_tariff(), optimistic).total_czk()
+                < minutes_only(basic_tariff(), pessimistic).total_czk()
+        );
+    }
+
+    #[test]
+    fn test_package_break_even() {
+        let legend = &basic_tariff().per_cartype[CarType::Legend];
+        // 2 hodiny + 10 km for 249 Kč beats 36 minutes at 6.99 Kč.
+        let morning = date(2025, 6, 3).at(10, 0, 0, 0);
+        assert_eq!(legend.package_break_even(morning), Some(SignedDuration::from_mins(36)));
+        // 30 minutes at 6.99 Kč, then 4 at 7.99 Kč are still cheaper.
+        let evening = date(2025, 6, 3).at(19, 30, 0, 0);
+        assert_eq!(legend.package_break_even(evening), Some(SignedDuration::from_mins(35)));
+
+        let minutes_czk = |minutes| {
+            minutes_only(
+                basic_tariff(),
+                TripInputData::from_times(
+                    0.0,
+                    morning,
+                    morning + SignedDuration::from_mins(minutes),
+                ),
+            )
+            .total_czk()
+        };
+        assert!(minutes_czk(35) < 249.0 && minutes_czk(36) > 249.0);
+
+        let no_packages = PerCarTariff { packages: vec![], ..legend.clone() };
+        assert_eq!(no_packages.package_break_even(morning), None);
+    }
+
+    #[test]
+    fn test_started_minutes_billed() {
+        let tariff = PerMinuteTariff {
+            start: Time::constant(6, 0, 0, 0),
+            end: Time::constant(20, 0, 0, 0),
+            weekdays: WeekdayRange::ALL,
+            per_minute_czk: 1.0,
+        };
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let billed = |seconds: i64| {
+            let mut cursor = begin;
+            let mut result = CalculationResult::new("");
+            let end = begin + seconds.seconds();
+            tariff.advance(&mut cursor, end, Rounding::default(), &mut result);
+            assert_eq!(cursor, end);
+            result.total_czk()
+        };
+
+        assert_eq!(billed(1), 1.0);
+        assert_eq!(billed(59), 1.0);
+        assert_eq!(billed(60), 1.0);
+        assert_eq!(billed(61), 2.0);
+    }
+
+    #[test]
+    fn test_reservation_beyond_free_window() {
+        let rows = "Rezervace zdarma (min) \t\t15 \t\nRezervace (Kč/min) \t\t2 \t\n";
+        let tariff = load_tariff(TariffKind::Basic, &[BASIC, rows.as_bytes()].concat()).unwrap();
+        assert_eq!(tariff.free_reservation, SignedDuration::from_mins(15));
+
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let trip = TripInputData::from_times(0.0, begin, begin + 1.hour());
+        let reserved = |minutes: i64| {
+            let input_data =
+                TripInputData { reservation_begin: Some(begin - minutes.minutes()), ..trip };
+            minutes_only(&tariff, input_data)
+        };
+
+        let within = reserved(10);
+        assert_eq!(within, minutes_only(&tariff, trip));
+        let beyond = reserved(40);
+        assert_eq!(beyond.components[0].name, "rezervace 25 minut");
+        assert_eq!(beyond.components[0].description.as_deref(), Some("25 minut × 2 Kč/min"));
+        assert_eq!(beyond.total_czk(), within.total_czk() + 25.0 * 2.0);
+        assert_eq!(reserved(-10), within, "reservation after the pickup costs nothing");
+
+        // Tariffs without the reservation rate don't bill it at all.
+        let long_reservation = TripInputData { reservation_begin: Some(begin - 1.day()), ..trip };
+        assert_eq!(
+            minutes_only(basic_tariff(), long_reservation),
+            minutes_only(basic_tariff(), trip)
+        );
+    }
+
+    #[test]
+    fn test_min_total_top_up() {
+        let data = [BASIC, "Minimální cena pronájmu (Kč) \t\t100 \t\n".as_bytes()].concat();
+        let tariff = load_tariff(TariffKind::Basic, &data).unwrap();
+        assert_eq!(tariff.min_total_czk, Some(100.0));
+        assert_eq!(basic_tariff().min_total_czk, None);
+
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let short = TripInputData::from_times(1.0, begin, begin + 5.minutes());
+        let result = minutes_only(&tariff, short);
+        let last = result.components.last().unwrap();
+        assert_eq!(last.name, "doplatek do minima");
+        assert!((last.czk - (100.0 - 5.0 * 6.99 - 9.49)).abs() < 1e-9);
+        assert!((result.total_czk() - 100.0).abs() < 1e-9);
+
+        // Longer trips are above the minimum, no top-up.
+        let long = TripInputData::from_times(1.0, begin, begin + 30.minutes());
+        let result = minutes_only(&tariff, long);
+        assert!(result.components.iter().all(|c| c.name != "doplatek do minima"));
+    }
+
+    #[test]
+    fn test_stacked_packages() {
+        let tariff = basic_tariff();
+        // 72 hours: 48 hodin + 24 hodin beat both 48 hodin + minutes and 4 dny.
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(50.0, begin, begin + 3.days());
+
+        let result = tariff.calculate_for_car(input_data, CarType::Legend, None).unwrap();
+        let names: Vec<_> = result.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["24 hodin + 20 km", "48 hodin + 40 km"]);
+        assert_eq!(result.package.as_deref(), Some("24 hodin + 20 km a 48 hodin + 40 km"));
+        assert_eq!(result.total_czk(), 699.0 + 1190.0);
+
+        let per_car_tariff = &tariff.per_cartype[CarType::Legend];
+        let package =
+            per_car_tariff.packages.iter().find(|p| p.name == "48 hodin + 40 km").unwrap();
+        let single = tariff
+            .calculate_for_package(
+                input_data,
+                CarType::Legend,
+                &per_car_tariff.per_minute,
+                Some(package),
+                None,
+            )
+            .unwrap();
+        assert!(result.total_czk() < single.total_czk(), "{result:?} vs {single:?}");
+
+        // Repeated packages are billed as one component.
+        let four_days = TripInputData { end: begin + 4.days(), ..input_data };
+        let doubled = tariff
+            .calculate_for_packages(
+                four_days,
+                CarType::Legend,
+                &per_car_tariff.per_minute,
+                &[package, package],
+                None,
+            )
+            .unwrap();
+        let components: Vec<_> =
+            doubled.components.iter().map(|c| (c.name.as_str(), c.czk)).collect();
+        assert_eq!(components, [("2× 48 hodin + 40 km", 2.0 * 1190.0)]);
+
+        // 2 days and 2 hours.
+        let input_data = TripInputData { end: begin + 50.hours(), ..input_data };
+        let packages: Vec<_> = per_car_tariff.packages.iter().collect();
+        let stacks = package_stacks(&packages, Duration::from_secs(50 * 60 * 60));
+        assert!(stacks.iter().all(|stack| stack.len() <= 3));
+        let redundant = stacks
+            .iter()
+            .find(|stack| stack.len() > 1 && stack.iter().any(|p| p.name == "4 dny + 150 km"));
+        assert_eq!(redundant, None, "4 dny alone cover the trip, nothing to stack with");
+        let result = tariff.calculate_for_car(input_data, CarType::Legend, None).unwrap();
+        assert_eq!(result.package.as_deref(), Some("2 hodiny + 10 km a 48 hodin + 40 km"));
+    }
+
+    #[test]
+    fn test_daily_cap() {
+        let data = String::from_utf8(BASIC.to_vec()).unwrap();
+        let data = format!("{data}Denní strop\t500\t700\t900\n");
+        let capped = load_tariff(TariffKind::Basic, data.as_bytes()).unwrap();
+        assert_eq!(capped.per_cartype[CarType::Boss].daily_cap_czk, Some(900.0));
+
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(0.0, begin, begin + 3.days());
+
+        let uncapped = minutes_only(basic_tariff(), input_data);
+        let per_day_czk = 14.0 * 60.0 * 6.99 + 10.0 * 60.0 * 7.99;
+        assert!((uncapped.total_czk() - 3.0 * per_day_czk).abs() < 1e-6);
+
+        let result = minutes_only(&capped, input_data);
+        assert!((result.total_czk() - 3.0 * 500.0).abs() < 1e-6, "{result:?}");
+        let cap = result.components.iter().find(|c| c.name == "denní strop").unwrap();
+        assert_eq!(cap.description.as_deref(), Some("nejvýše 500 Kč za 24 hodin"));
+
+        // The package covers the first day, just the minutes after it are capped.
+        let per_car_tariff = &capped.per_cartype[CarType::Legend];
+        let package =
+            per_car_tariff.packages.iter().find(|p| p.name == "24 hodin + 20 km").unwrap();
+        let result = capped
+            .calculate_for_package(
+                input_data,
+                CarType::Legend,
+                &per_car_tariff.per_minute,
+                Some(package),
+                None,
+            )
+            .unwrap();
+        assert!((result.total_czk() - (699.0 + 2.0 * 500.0)).abs() < 1e-6, "{result:?}");
+
+        // Short trips stay below the cap.
+        let short = TripInputData { end: begin + 1.hour(), ..input_data };
+        assert_eq!(minutes_only(&capped, short), minutes_only(basic_tariff(), short));
+    }
+
+    #[test]
+    fn test_zero_duration_package_rejected() {
+        let data = [BASIC, "0 hodin + 50 km \t100 \t150 \t200 \n".as_bytes()].concat();
+        let error = load_tariff(TariffKind::Basic, &data).unwrap_err();
+        assert_eq!(error.to_string(), "Package 0 hodin + 50 km has zero duration");
+    }
+
+    #[test]
+    fn test_trip_limits() {
+        let data = [BASIC, "Maximální nájezd (km) \t\t500 \t\n".as_bytes()].concat();
+        let tariff = load_tariff(TariffKind::Basic, &data).unwrap();
+        assert_eq!(tariff.limits, TripLimits { max_duration: None, max_km: Some(500.0) });
+
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(400.0, begin, begin + 1.day());
+        assert_eq!(tariff.limits.unavailable_reason(input_data), None);
+
+        let input_data = TripInputData::from_times(600.0, begin, begin + 1.day());
+        assert_eq!(
+            tariff.limits.unavailable_reason(input_data).as_deref(),
+            Some("nelze: trasa přesahuje maximum 500 km")
+        );
+
+        assert_eq!(basic_tariff().limits, TripLimits::default());
+    }
+
+    #[test]
+    fn test_overnight_scenario() {
+        let begin = date(2025, 6, 3).at(12, 0, 0, 0);
+        // Tuesday evening to Wednesday morning.
+        let input_data = TripInputData::from_times(0.0, begin, begin).overnight(date(2025, 6, 3));
+        assert_eq!(input_data.km, 5.0);
+
+        let names: Vec<_> = minutes_only(basic_tariff(), input_data)
+            .components
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "minutový tarif 06-20h 60 minut",
+                "minutový tarif 20-06h 600 minut",
+                "minutový tarif 06-20h 60 minut",
+                "extra za 5 km",
+            ]
+        );
+
+        for (kind, expected_czk) in
+            [(TariffKind::Basic, 699.0), (TariffKind::Active, 665.0), (TariffKind::Business, 630.0)]
+        {
+            let car4way = Car4way { tariff: kind, ..Car4way::default() };
+            let result = car4way.calculate(input_data).unwrap();
+            assert_eq!(result.package.as_deref(), Some("24 hodin + 20 km"), "{kind}");
+            assert_eq!(result.car_type, CarType::Legend.name(Lang::default()), "{kind}");
+            assert_eq!(result.total_czk(), expected_czk, "{kind}");
+        }
+    }
+
+    #[test]
+    fn test_out_of_zone_return() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let in_zone = TripInputData::from_times(5.0, begin, begin + 30.minutes());
+        let out_of_zone = TripInputData { return_location: ReturnLocation::OutOfZone, ..in_zone };
+
+        let car4way = Car4way { out_of_zone_return_czk: 300.0, ..Car4way::default() };
+        let in_zone = car4way.calculate(in_zone).unwrap();
+        let out_of_zone = car4way.calculate(out_of_zone).unwrap();
+        assert_eq!(out_of_zone.total_czk(), in_zone.total_czk() + 300.0);
+    }
+
+    #[test]
+    fn test_weekday_shift_weekend_package_availability() {
+        let weekend_package = basic_tariff().per_cartype[CarType::Legend]
+            .packages
+            .iter()
+            .find(|p| p.name == "Víkend + 200 km")
+            .unwrap();
+
+        // Tuesday 2025-06-03.
+        let begin = date(2025, 6, 3).at(17, 0, 0, 0);
+        let tuesday = TripInputData::from_times(100.0, begin, begin + 20.hours());
+        assert!(!weekend_package.is_available(tuesday.begin, tuesday.end));
+
+        let saturday = tuesday.with_begin_weekday(Weekday::Saturday);
+        assert_eq!(saturday.begin, date(2025, 6, 7).at(17, 0, 0, 0));
+        assert_eq!(
+            saturday.end.duration_since(saturday.begin),
+            tuesday.end.duration_since(tuesday.begin)
+        );
+        assert!(weekend_package.is_available(saturday.begin, saturday.end));
+    }
+
+    #[test]
+    fn test_weekend_package_chosen_only_on_weekend() {
+        let tariff = basic_tariff();
+        let chosen_package = |input_data| {
+            tariff.calculate_for_car(input_data, CarType::Legend, None).unwrap().package
+        };
+
+        // Saturday 2025-06-07 10:00 to Sunday 20:00.
+        let begin = date(2025, 6, 7).at(10, 0, 0, 0);
+        let weekend = TripInputData::from_times(400.0, begin, begin + 34.hours());
+        assert_eq!(chosen_package(weekend).as_deref(), Some("Víkend + 200 km"));
+
+        // The same trip starting on Tuesday must not use the weekend package.
+        let midweek = weekend.with_begin_weekday(Weekday::Tuesday);
+        assert_eq!(midweek.begin.weekday(), Weekday::Tuesday);
+        let package = chosen_package(midweek);
+        assert!(package.is_some(), "some other package is still cheaper than minutes");
+        assert_ne!(package.as_deref(), Some("Víkend + 200 km"));
+    }
+
+    #[test]
+    fn test_airport_surcharges_added_once() {
+        let tariff = basic_tariff();
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData {
+            airport_enter: true,
+            airport_leave: true,
+            ..TripInputData::from_times(30.0, begin, begin + 90.minutes())
+        };
+
+        for car_type in CarType::iter() {
+            let per_car_tariff = &tariff.per_cartype[car_type];
+            let packages = per_car_tariff.packages.iter().map(Some).chain(Some(None));
+            for package in packages {
+                let result = tariff
+                    .calculate_for_package(
+                        input_data,
+                        car_type,
+                        &per_car_tariff.per_minute,
+                        package,
+                        None,
+                    )
+                    .unwrap();
+                for name in ["letiště Praha - příjezd", "letiště Praha - výjezd"] {
+                    let surcharges: Vec<_> =
+                        result.components.iter().filter(|c| c.name == name).collect();
+                    assert_eq!(surcharges.len(), 1, "{name} in {result:?}");
+                    assert_eq!(surcharges[0].czk, 199.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_components_sum_to_total() {
+        let tariff = basic_tariff();
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData {
+            airport_enter: true,
+            ..TripInputData::from_times(15.0, begin, begin + 100.minutes())
+        };
+
+        let result = tariff.calculate_for_car(input_data, CarType::Legend, None).unwrap();
+        assert_eq!(result.car_type, CarType::Legend.name(Lang::default()));
+        let names: Vec<_> = result.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["2 hodiny + 10 km", "extra za 5 km", "letiště Praha - příjezd"]);
+
+        let expected_czk = 249.0 + 5.0 * 9.49 + 199.0;
+        let sum_czk: f64 = result.components.iter().map(|c| c.czk).sum();
+        assert!((sum_czk - expected_czk).abs() < 1e-9, "{sum_czk} != {expected_czk}");
+        assert_eq!(result.total_czk(), sum_czk);
+    }
+
+    #[test]
+    fn test_custom_weekend_window() {
+        let weekend = |tariff: &Tariff| {
+            tariff.per_cartype[CarType::Legend]
+                .packages
+                .iter()
+                .find(|p| p.name.starts_with("Víkend"))
+                .cloned()
+                .unwrap()
+        };
+        let default = weekend(basic_tariff());
+        assert_eq!(default.duration, Duration::from_secs(66 * 60 * 60));
+
+        let data = String::from_utf8(BASIC.to_vec())
+            .unwrap()
+            .replace("Víkend + 200 km", "Víkend (Pá 12:00 - Po 8:00) + 200 km");
+        let tariff = load_tariff(TariffKind::Basic, data.as_bytes()).unwrap();
+        let custom = weekend(&tariff);
+        assert_eq!(
+            custom.time_limitation,
+            Some(TimeLimitation {
+                from: WeekdayTime { weekday: Weekday::Friday, time: Time::constant(12, 0, 0, 0) },
+                to: WeekdayTime { weekday: Weekday::Monday, time: Time::constant(8, 0, 0, 0) },
+            })
+        );
+        assert_eq!(custom.duration, Duration::from_secs(68 * 60 * 60));
+        assert_eq!((custom.kilometers, custom.czk), (default.kilometers, default.czk));
+
+        // Friday 2025-06-06 afternoon to Sunday.
+        let begin = date(2025, 6, 6).at(13, 0, 0, 0);
+        let end = date(2025, 6, 8).at(18, 0, 0, 0);
+        assert!(custom.is_available(begin, end));
+        assert!(!default.is_available(begin, end));
+        // Until Monday 9:00 fits just the default one.
+        let end = date(2025, 6, 9).at(9, 0, 0, 0);
+        assert!(!custom.is_available(date(2025, 6, 6).at(17, 0, 0, 0), end));
+        assert!(default.is_available(date(2025, 6, 6).at(17, 0, 0, 0), end));
+
+        let data = data.replace("(Pá 12:00", "(Xy 12:00");
+        let error = load_tariff(TariffKind::Basic, data.as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "Unknown weekday Xy");
+    }
+
+    #[test]
+    fn test_extension_after_package_km_used() {
+        let car4way = Car4way::default();
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        // 1 hour and 15 km into "2 hodiny + 10 km": 5 km over the package already.
+        let elapsed = TripInputData::from_times(15.0, begin, begin + 1.hour());
+        let package = Some("2 hodiny + 10 km");
+
+        // Still within the package time, so just the extra km. Difference of invoiced totals,
+        // i.e. rounded 296.45 and 391.35.
+        let cost = car4way.extension_cost(elapsed, 30, 10.0, CarType::Legend, package).unwrap();
+        assert_eq!(cost, 391.0 - 296.0);
+
+        // Beyond the package time, the minutes are billed too.
+        let cost = car4way.extension_cost(elapsed, 90, 10.0, CarType::Legend, package).unwrap();
+        assert_eq!(cost, (249.0 + 15.0 * 9.49 + 30.0 * 6.99_f64).round() - 296.0);
+
+        let error =
+            car4way.extension_cost(elapsed, 30, 0.0, CarType::Legend, Some("nic")).unwrap_err();
+        assert_eq!(error.to_string(), "unknown package nic");
+    }
+
+    #[test]
+    fn test_extension_out_of_range() {
+        let car4way = Car4way::default();
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let elapsed = TripInputData::from_times(15.0, begin, begin + 1.hour());
+
+        let error =
+            car4way.extension_cost(elapsed, i64::MAX, 0.0, CarType::Legend, None).unwrap_err();
+        assert_eq!(error.to_string(), format!("extension by {} minutes, not 0 to 44640", i64::MAX));
+        assert!(car4way.extension_cost(elapsed, -1, 0.0, CarType::Legend, None).is_err());
+
+        let elapsed = TripInputData::from_times(15.0, DateTime::MAX - 1.hour(), DateTime::MAX);
+        let error = car4way.extension_cost(elapsed, 60, 0.0, CarType::Legend, None).unwrap_err();
+        assert_eq!(error.to_string(), "extended end out of range");
+    }
+
+    #[test]
+    fn test_car_type_results() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(0.0, begin, begin + 10.minutes());
+        let totals = |car4way: Car4way| -> Vec<_> {
+            let results = car4way.car_type_results(input_data).unwrap();
+            results.into_iter().map(|(car_type, result)| (car_type, result.total_czk())).collect()
+        };
+
+        // Rounded to whole crowns like the invoice.
+        assert_eq!(
+            totals(Car4way::default()),
+            [(CarType::Legend, 70.0), (CarType::Fancy, 90.0), (CarType::Boss, 110.0)]
+        );
+
+        let car4way = Car4way {
+            car_types: [CarType::Boss, CarType::Fancy].into(),
+            insurance: Insurance { enabled: true, per_day_czk: 100.0 },
+            ..Car4way::default()
+        };
+        assert_eq!(totals(car4way.clone()), [(CarType::Fancy, 190.0), (CarType::Boss, 210.0)]);
+        let cheapest = car4way.calculate(input_data).unwrap();
+        assert_eq!(cheapest.car_type, CarType::Fancy.name(Lang::default()));
+    }
+
+    #[test]
+    fn test_car_type_results_match_headline() {
+        with_signals(|| {
+            let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+            let input_data = TripInputData {
+                round_trip: true,
+                discount: Discount { kind: DiscountKind::Percent, amount: 10.0 },
+                ..TripInputData::from_times(20.0, begin, begin + 1.hour())
+            };
+            let car4way = Car4way::default();
+            let provider = Provider::new(Signal::new(true), Signal::new(car4way.clone()));
+
+            // What `Provider::render_input()` hands over to the details.
+            let results = car4way.car_type_results(input_data.effective()).unwrap();
+            let headline = provider.calculate(input_data).unwrap();
+            assert_eq!(results[0].1.total_czk(), headline.total_czk());
+        })
+    }
+
+    #[test]
+    fn test_no_car_types() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(10.0, begin, begin + 1.hour());
+        let car4way = Car4way { car_types: BTreeSet::new(), ..Car4way::default() };
+
+        assert_eq!(
+            car4way.unavailable_reason(input_data).as_deref(),
+            Some("nelze: žádná kategorie vybrána")
+        );
+        assert_eq!(car4way.calculate(input_data).unwrap_err().to_string(), NO_CAR_TYPES);
+        assert!(car4way.car_type_results(input_data).unwrap().is_empty());
+        assert_eq!(car4way.recommend_tariff(begin.date()), None);
+    }
+
+    #[test]
+    fn test_total_without_packages() {
+        with_signals(|| {
+            let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+            let input_data = TripInputData::from_times(10.0, begin, begin + 2.hours());
+            let car4way = |car4way| Provider::new(Signal::new(true), Signal::new(car4way));
+            let basic = car4way(Car4way::default());
+            let active = car4way(Car4way { tariff: TariffKind::Active, ..Car4way::default() });
+            let walk = Provider::new(Signal::new(true), Signal::new(Walk::default()));
+
+            let figures = |provider: &Provider| {
+                let best = provider.calculate(input_data).unwrap();
+                let per_minute = provider.total_without_packages(input_data).unwrap().unwrap();
+                (best.total_czk(), best.package, per_minute)
+            };
+            // "2 hodiny + 10 km" packages vs. 120 minutes at day rate of Legend plus the km.
+            let package = Some("2 hodiny + 10 km".to_string());
+            assert_eq!(
+                figures(&basic),
+                (249.0, package.clone(), (120.0 * 6.99 + 10.0 * 9.49_f64).round())
+            );
+            assert_eq!(
+                figures(&active),
+                (235.0, package, (120.0 * 5.99 + 10.0 * 7.99_f64).round())
+            );
+
+            assert!(walk.total_without_packages(input_data).is_none());
+        })
+    }
+
+    #[test]
+    fn test_zero_duration_trip_charges_km_only() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(12.0, begin, begin);
+        assert!(input_data.is_km_only());
+
+        let result = Car4way::default().calculate(input_data).unwrap();
+        assert_eq!(result.components.len(), 1);
+        assert_eq!(result.components[0].name, "extra za 12 km");
+        assert_eq!(result.total_czk(), 12.0 * 9.49);
+    }
+
+    #[test]
+    fn test_zero_and_negative_length_trip() {
+        let car_types = BTreeSet::from([CarType::Legend]);
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        for end in [begin, begin - 30.minutes()] {
+            let input_data = TripInputData::from_times(12.0, begin, end);
+            let result = basic_tariff().calculate(input_data, &car_types, None).unwrap();
+            let names: Vec<_> = result.components.iter().map(|c| c.name.as_str()).collect();
+            assert_eq!(names, ["extra za 12 km"], "trip ending at {end}");
+            assert_eq!(result.total_czk(), 12.0 * 9.49);
+        }
+    }
+
+    #[test]
+    fn test_recommend_tariff_heavy_usage() {
+        let car4way = Car4way {
+            monthly_usage: MonthlyUsage {
+                trips: 20,
+                total_km: 600.0,
+                trip_minutes: 180,
+                business_customer: false,
+            },
+            ..Car4way::default()
+        };
+        assert_eq!(car4way.recommend_tariff(date(2025, 6, 1)), Some(TariffKind::Active));
+    }
+
+    #[test]
+    fn test_monthly_usage_out_of_range() {
+        let month = date(2025, 6, 1);
+        let tariff = |kind| tariffs_on(month).unwrap().tariff(kind).unwrap();
+        let car_types = CarType::iter().collect();
+        for trip_minutes in [i64::MAX, -30] {
+            let usage = MonthlyUsage { trip_minutes, ..MonthlyUsage::default() };
+            let error = tariff(TariffKind::Basic).calculate_month(&usage, month, &car_types);
+            assert_eq!(error.unwrap_err().to_string(), format!("trip of {trip_minutes} minutes"));
+
+            let car4way = Car4way { monthly_usage: usage, ..Car4way::default() };
+            assert_eq!(car4way.recommend_tariff(month), None);
+        }
+    }
+
+    #[test]
+    fn test_cheapest_of_all_tariffs() {
+        let begin = date(2025, 6, 3).at(12, 0, 0, 0);
+        let input_data = TripInputData::from_times(0.0, begin, begin).overnight(date(2025, 6, 3));
+
+        let results = Car4way::default().calculate_all_tariffs(input_data);
+        let totals: Vec<_> = results
+            .iter()
+            .map(|(kind, result)| (*kind, result.as_ref().unwrap().as_ref().unwrap().total_czk()))
+            .collect();
+        assert_eq!(
+            totals,
+            [
+                (TariffKind::Basic, 699.0),
+                (TariffKind::Active, 665.0),
+                (TariffKind::Business, 630.0)
+            ]
+        );
+        assert_eq!(Car4way::cheapest_tariff(&results), Some(TariffKind::Business));
+
+        // Active and Business share the daytime rate, a tie goes to the first in the lineup.
+        let input_data = TripInputData::from_times(0.0, begin, begin + 20.minutes());
+        let results = Car4way::default().calculate_all_tariffs(input_data);
+        assert_eq!(Car4way::cheapest_tariff(&results), Some(TariffKind::Active));
+    }
+
+    #[test]
+    fn test_idle_minutes_billed_at_idle_rate() {
+        let tariff = basic_tariff();
+        let per_minute = &tariff.per_cartype[CarType::Legend].per_minute;
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData {
+            idle_minutes: 30,
+            ..TripInputData::from_times(0.0, begin, begin + 90.minutes())
+        };
+
+        let result = tariff
+            .calculate_for_package(input_data, CarType::Legend, per_minute, None, Some(2.0))
+            .unwrap();
+        let components: Vec<_> = result.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(components, ["minutový tarif 06-20h 60 minut", "parkovné 30 minut"]);
+        assert_eq!(result.total_czk(), 60.0 * 6.99 + 30.0 * 2.0);
+
+        // Without a reduced rate, idle minutes are just regular ones.
+        let regular = minutes_only(tariff, input_data);
+        assert_eq!(regular.total_czk(), 90.0 * 6.99);
+    }
+
+    #[test]
+    fn test_idle_rate_from_tariff() {
+        let data = String::from_utf8(BASIC.to_vec()).unwrap();
+        let data = format!("{data}Parkovné\t2\t3\t4\n");
+        let tariff = load_tariff(TariffKind::Basic, data.as_bytes()).unwrap();
+        assert_eq!(tariff.per_cartype[CarType::Fancy].idle_per_minute_czk, Some(3.0));
+
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let driving = TripInputData::from_times(0.0, begin, begin + 3.hours());
+        let parked = TripInputData { idle_minutes: 150, ..driving };
+        let total = |input_data, idle_per_minute_czk| {
+            let per_minute = &tariff.per_cartype[CarType::Legend].per_minute;
+            tariff
+                .calculate_for_package(
+                    input_data,
+                    CarType::Legend,
+                    per_minute,
+                    None,
+                    idle_per_minute_czk,
+                )
+                .unwrap()
+                .total_czk()
+        };
+
+        assert_eq!(total(driving, None), 180.0 * 6.99);
+        assert_eq!(total(parked, None), 30.0 * 6.99 + 150.0 * 2.0);
+        assert_eq!(total(parked, Some(1.0)), 30.0 * 6.99 + 150.0, "set in the UI wins");
     }
 }
@@ -0,0 +1,401 @@
+//! Composable pricing rules. Each [`Tariff`] applies its rules in order, every rule may emit
+//! price components and consume part of the trip (time or kilometers) for the following rules.
+
+use crate::{
+    TripInputData,
+    provider::{
+        CalculationResult, after,
+        car4way::{KmTier, Package, PerMinuteTariff, Tariff, per_minute_description},
+        elapsed,
+    },
+};
+use anyhow::{Result, bail};
+use jiff::{
+    SignedDuration,
+    civil::{DateTime, Time},
+};
+use std::{collections::VecDeque, fmt::Debug, iter, sync::Arc};
+use tracing::error;
+
+pub(super) trait PricingRule: Debug + Send + Sync {
+    fn apply(
+        &self,
+        tariff: &Tariff,
+        trip: &mut TripState,
+        result: &mut CalculationResult,
+    ) -> Result<()>;
+}
+
+/// The part of the trip not yet billed by previous rules.
+#[derive(Debug)]
+pub(super) struct TripState<'a> {
+    pub(super) input_data: TripInputData,
+    pub(super) per_minute: &'a [PerMinuteTariff],
+    /// Packages to use one after another, possibly none.
+    pub(super) packages: &'a [&'a Package],
+    /// Reduced rate for [`TripInputData::idle_minutes`], `None` if idle time is billed normally.
+    pub(super) idle_per_minute_czk: Option<f64>,
+    /// See [`PerCarTariff::daily_cap_czk`](super::PerCarTariff::daily_cap_czk).
+    pub(super) daily_cap_czk: Option<f64>,
+    /// Everything before the cursor is already billed.
+    pub(super) cursor: DateTime,
+    pub(super) remaining_km: f64,
+}
+
+impl TripState<'_> {
+    /// End of the time billed at regular rates, idle minutes at the end of the trip are after it.
+    fn regular_end(&self) -> DateTime {
+        let input_data = &self.input_data;
+        match self.idle_per_minute_czk {
+            Some(_) => {
+                let idle = SignedDuration::from_mins(input_data.idle_minutes);
+                (input_data.end - idle).max(input_data.begin)
+            },
+            None => input_data.end,
+        }
+    }
+}
+
+pub(super) fn default_rules() -> Vec<Arc<dyn PricingRule>> {
+    vec![
+        Arc::new(ReservationRule),
+        Arc::new(PackageRule),
+        Arc::new(MinuteRule),
+        Arc::new(IdleRule),
+        Arc::new(ExtraKmRule),
+        Arc::new(AirportRule),
+    ]
+}
+
+/// Bill the reservation before the rental beyond its free part, packages don't cover it.
+#[derive(Debug)]
+struct ReservationRule;
+
+impl PricingRule for ReservationRule {
+    fn apply(
+        &self,
+        tariff: &Tariff,
+        trip: &mut TripState,
+        result: &mut CalculationResult,
+    ) -> Result<()> {
+        let input_data = &trip.input_data;
+        let (Some(reservation_begin), Some(per_minute_czk)) =
+            (input_data.reservation_begin, tariff.reservation_per_minute_czk)
+        else {
+            return Ok(());
+        };
+        let billed = elapsed(reservation_begin, input_data.begin) - tariff.free_reservation;
+        if billed.is_positive() {
+            let minutes = input_data.rounding.minutes(billed);
+            result.add_described_component(
+                minutes as f64 * per_minute_czk,
+                format!("rezervace {minutes} minut"),
+                per_minute_description(minutes, per_minute_czk),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Bill the selected package (if any), consuming its time and kilometers.
+#[derive(Debug)]
+struct PackageRule;
+
+impl PricingRule for PackageRule {
+    fn apply(
+        &self,
+        _tariff: &Tariff,
+        trip: &mut TripState,
+        result: &mut CalculationResult,
+    ) -> Result<()> {
+        if trip.packages.is_empty() {
+            return Ok(());
+        }
+
+        let mut names = vec![];
+        // Repeated packages are next to each other, bill them together.
+        for same in trip.packages.chunk_by(|a, b| a.name == b.name) {
+            let package = same[0];
+            let name = match same.len() {
+                1 => package.name.clone(),
+                count => format!("{count}× {}", package.name),
+            };
+            for package in same {
+                // Packages last real hours, a DST transition makes them end at another wall time.
+                trip.cursor = after(trip.cursor, SignedDuration::try_from(package.duration)?);
+                trip.remaining_km -= package.kilometers;
+            }
+            result.add_component(same.len() as f64 * package.czk, name.clone());
+            names.push(name);
+        }
+        trip.remaining_km = trip.remaining_km.max(0.0);
+
+        result.package = Some(names.join(" a "));
+        Ok(())
+    }
+}
+
+/// Sane upper bound of minute tariff segments between `begin` and `end`, guards against
+/// [`PerMinuteTariff::advance()`] not progressing or tariffs being needlessly fragmented.
+fn max_minute_segments(begin: DateTime, end: DateTime) -> u64 {
+    const MAX_SEGMENTS_PER_DAY: u64 = 48;
+
+    let days = end.duration_since(begin).as_hours().max(0) as u64 / 24 + 1;
+    days * MAX_SEGMENTS_PER_DAY
+}
+
+/// Most that can be billed for minutes in any 24 hours, each minute is billed as much of its price
+/// as the minutes before it leave.
+#[derive(Debug)]
+pub(super) struct RollingCap {
+    cap_czk: f64,
+    /// Billed price of up to the last 24 hours of minutes, oldest first.
+    window: VecDeque<f64>,
+    window_czk: f64,
+}
+
+impl RollingCap {
+    const WINDOW_MINUTES: usize = 24 * 60;
+
+    pub(super) fn new(cap_czk: f64) -> Self {
+        Self { cap_czk, window: VecDeque::with_capacity(Self::WINDOW_MINUTES), window_czk: 0.0 }
+    }
+
+    /// Bill the next minute costing `czk`, returns how much of it is billed.
+    pub(super) fn bill_minute(&mut self, czk: f64) -> f64 {
+        if self.window.len() == Self::WINDOW_MINUTES {
+            self.window_czk -= self.window.pop_front().expect("window is full");
+        }
+        let billed_czk = czk.min((self.cap_czk - self.window_czk).max(0.0));
+        self.window.push_back(billed_czk);
+        self.window_czk += billed_czk;
+        billed_czk
+    }
+}
+
+/// Bill the rest of the trip time using minute tariffs.
+#[derive(Debug)]
+struct MinuteRule;
+
+impl PricingRule for MinuteRule {
+    fn apply(
+        &self,
+        _tariff: &Tariff,
+        trip: &mut TripState,
+        result: &mut CalculationResult,
+    ) -> Result<()> {
+        let trip_end = trip.regular_end();
+        let max_segments = max_minute_segments(trip.cursor, trip_end);
+        let mut segments = 0;
+        // Billed minutes and their rate, in order, for the daily cap.
+        let mut billed = vec![];
+
+        while trip.cursor < trip_end {
+            segments += 1;
+            if segments > max_segments {
+                error!(
+                    "Minute billing exceeded {max_segments} segments at {} for {:?} with {:?}.",
+                    trip.cursor, trip.input_data, trip.per_minute
+                );
+                bail!("minute billing did not finish in {max_segments} segments");
+            }
+
+            let tariff_at = |datetime: DateTime| {
+                trip.per_minute
+                    .iter()
+                    .find(|minute_tariff| minute_tariff.applies_at(datetime))
+                    .expect("minute tariffs cover 24 hours of every weekday")
+            };
+            let minute_tariff = tariff_at(trip.cursor);
+            // A tariff of other weekdays may take over at midnight.
+            let midnight = trip.cursor.date().tomorrow()?.to_datetime(Time::midnight());
+            let end = if midnight < trip_end && tariff_at(midnight) != minute_tariff {
+                midnight
+            } else {
+                trip_end
+            };
+
+            let minutes =
+                minute_tariff.advance(&mut trip.cursor, end, trip.input_data.rounding, result);
+            billed.push((minutes, minute_tariff.per_minute_czk));
+        }
+
+        if let Some(cap_czk) = trip.daily_cap_czk {
+            let mut rolling_cap = RollingCap::new(cap_czk);
+            let uncapped_czk: f64 = billed.iter().map(|&(minutes, czk)| minutes as f64 * czk).sum();
+            let capped_czk: f64 = billed
+                .iter()
+                .flat_map(|&(minutes, czk)| iter::repeat_n(czk, minutes.max(0) as usize))
+                .map(|czk| rolling_cap.bill_minute(czk))
+                .sum();
+            if capped_czk < uncapped_czk {
+                result.add_described_component(
+                    capped_czk - uncapped_czk,
+                    "denní strop",
+                    format!("nejvýše {cap_czk} Kč za 24 hodin"),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bill the idle minutes not covered by a package at the reduced rate.
+#[derive(Debug)]
+struct IdleRule;
+
+impl PricingRule for IdleRule {
+    fn apply(
+        &self,
+        _tariff: &Tariff,
+        trip: &mut TripState,
+        result: &mut CalculationResult,
+    ) -> Result<()> {
+        let Some(idle_per_minute_czk) = trip.idle_per_minute_czk else {
+            return Ok(());
+        };
+        let end = trip.input_data.end;
+        if trip.cursor < end {
+            let minutes = trip.input_data.rounding.minutes(elapsed(trip.cursor, end));
+            result.add_described_component(
+                minutes as f64 * idle_per_minute_czk,
+                format!("parkovné {minutes} minut"),
+                per_minute_description(minutes, idle_per_minute_czk),
+            );
+            trip.cursor = end;
+        }
+        Ok(())
+    }
+}
+
+/// Bill the kilometers not covered by a package.
+#[derive(Debug)]
+struct ExtraKmRule;
+
+impl PricingRule for ExtraKmRule {
+    fn apply(
+        &self,
+        tariff: &Tariff,
+        trip: &mut TripState,
+        result: &mut CalculationResult,
+    ) -> Result<()> {
+        if trip.remaining_km > 0.0 {
+            let remaining_km = trip.remaining_km;
+            result.add_component(
+                KmTier::czk(&tariff.per_km, remaining_km),
+                format!("extra za {remaining_km} km"),
+            );
+            trip.remaining_km = 0.0;
+        }
+        Ok(())
+    }
+}
+
+/// Flat fees for entering or leaving the Prague airport, regardless of package and car type.
+#[derive(Debug)]
+struct AirportRule;
+
+impl PricingRule for AirportRule {
+    fn apply(
+        &self,
+        tariff: &Tariff,
+        trip: &mut TripState,
+        result: &mut CalculationResult,
+    ) -> Result<()> {
+        if trip.input_data.airport_enter {
+            result.add_component(tariff.airport_enter_czk, "letiště Praha - příjezd");
+        }
+        if trip.input_data.airport_leave {
+            result.add_component(tariff.airport_leave_czk, "letiště Praha - výjezd");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::car4way::{CarType, TARIFFS, TariffKind, WeekdayRange};
+    use jiff::{
+        SignedDuration, ToSpan,
+        civil::{Time, date},
+    };
+    use test_log::test;
+
+    /// Example of an unusual tariff: the first (started) day of the rental costs extra.
+    #[derive(Debug)]
+    struct FirstDayPremium {
+        czk: f64,
+    }
+
+    impl PricingRule for FirstDayPremium {
+        fn apply(
+            &self,
+            _tariff: &Tariff,
+            trip: &mut TripState,
+            result: &mut CalculationResult,
+        ) -> Result<()> {
+            if trip.input_data.begin < trip.input_data.end {
+                result.add_component(self.czk, "příplatek za první den");
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_rolling_cap() {
+        let mut rolling_cap = RollingCap::new(100.0);
+        let mut bill =
+            |minutes, czk| -> f64 { (0..minutes).map(|_| rolling_cap.bill_minute(czk)).sum() };
+        // Cheap hours first, then the cap is reached just before a calendar day would end...
+        assert_eq!(bill(20 * 60, 0.0), 0.0);
+        assert_eq!(bill(4 * 60, 1.0), 100.0);
+        // ...and the following hours are still within the same 24 hours.
+        assert_eq!(bill(4 * 60, 1.0), 0.0);
+        // Once the window slides past the billed minutes, billing resumes.
+        assert_eq!(bill(16 * 60, 1.0), 0.0);
+        assert_eq!(bill(2 * 60, 1.0), 100.0);
+    }
+
+    #[test]
+    fn test_fragmented_minute_tariff_triggers_guard() {
+        let tariff = TARIFFS.as_ref().unwrap().last().unwrap().tariff(TariffKind::Basic).unwrap();
+        // A separate tariff for every minute of the day.
+        let per_minute: Vec<_> = (0..24 * 60)
+            .map(|minute| {
+                let start = Time::MIN + SignedDuration::from_mins(minute);
+                let end = start + SignedDuration::from_mins(1);
+                PerMinuteTariff { start, end, weekdays: WeekdayRange::ALL, per_minute_czk: 1.0 }
+            })
+            .collect();
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(0.0, begin, begin + 1.day());
+
+        let error = tariff
+            .calculate_for_package(input_data, CarType::Legend, &per_minute, None, None)
+            .unwrap_err();
+        assert_eq!(error.to_string(), "minute billing did not finish in 96 segments");
+    }
+
+    #[test]
+    fn test_first_day_premium_rule() {
+        let mut tariff =
+            TARIFFS.as_ref().unwrap().last().unwrap().tariff(TariffKind::Basic).unwrap().clone();
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(0.0, begin, begin + 1.hour());
+        let per_minute = tariff.per_cartype[CarType::Legend].per_minute.clone();
+
+        let plain = tariff
+            .calculate_for_package(input_data, CarType::Legend, &per_minute, None, None)
+            .unwrap();
+
+        tariff.rules.insert(0, Arc::new(FirstDayPremium { czk: 100.0 }));
+        let premium = tariff
+            .calculate_for_package(input_data, CarType::Legend, &per_minute, None, None)
+            .unwrap();
+
+        assert_eq!(premium.components[0].name, "příplatek za první den");
+        assert_eq!(premium.components[1..], plain.components[..]);
+        assert_eq!(premium.total_czk(), plain.total_czk() + 100.0);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{
+    FormEvent, TripInputData,
+    provider::{CalculationResult, InvoiceRounding, ProviderImpl},
+};
+use anyhow::Result;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Driving one's own car, paying just for the fuel.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OwnCar {
+    consumption_l_per_100km: f64,
+}
+
+impl ProviderImpl for OwnCar {
+    fn name(&self) -> &'static str {
+        "vlastní auto"
+    }
+
+    fn id(&self) -> &'static str {
+        "owncar"
+    }
+
+    fn calculate(&self, input_data: TripInputData) -> Result<CalculationResult> {
+        Ok(OwnCar::calculate(self, input_data))
+    }
+
+    /// No invoice for own car, just be precise.
+    fn invoice_rounding(&self) -> InvoiceRounding {
+        InvoiceRounding::Haler
+    }
+
+    fn render_input(own_car: Signal<Self>, _input_data: TripInputData) -> Element {
+        rsx! { OwnCarInput { own_car } }
+    }
+}
+
+impl OwnCar {
+    fn per_km_czk(&self, fuel_price_czk_per_l: f64) -> f64 {
+        self.consumption_l_per_100km / 100.0 * fuel_price_czk_per_l
+    }
+
+    pub fn calculate(&self, input_data: TripInputData) -> CalculationResult {
+        let per_km_czk = self.per_km_czk(input_data.fuel_price_czk_per_l);
+        let mut result = CalculationResult::new(self.name());
+        result.add_component(
+            input_data.km * per_km_czk,
+            format!("palivo {} km × {per_km_czk:.2} Kč/km", input_data.km),
+        );
+        result
+    }
+}
+
+impl Default for OwnCar {
+    fn default() -> Self {
+        Self { consumption_l_per_100km: 6.0 }
+    }
+}
+
+#[component]
+pub fn OwnCarInput(own_car: Signal<OwnCar>) -> Element {
+    let consumption_changed = move |evt: FormEvent| {
+        own_car.write().consumption_l_per_100km = evt.parsed()?;
+        Ok(())
+    };
+
+    rsx! {
+        p {
+            label { for: "provider-owncar-consumption", "Spotřeba (l/100 km): " },
+            input { id: "provider-owncar-consumption",
+                r#type: "number",
+                value: own_car.read().consumption_l_per_100km,
+                onchange: consumption_changed,
+                min: 0,
+                step: 0.1,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::{ToSpan, civil::date};
+    use test_log::test;
+
+    #[test]
+    fn test_fuel_cost() {
+        let own_car = OwnCar { consumption_l_per_100km: 6.0 };
+        assert_eq!(own_car.per_km_czk(38.0), 2.28);
+
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData {
+            fuel_price_czk_per_l: 38.0,
+            ..TripInputData::from_times(100.0, begin, begin + 2.hours())
+        };
+        let result = own_car.calculate(input_data);
+        assert_eq!(result.components.len(), 1);
+        assert!((result.total_czk() - 228.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_time_is_irrelevant() {
+        let own_car = OwnCar::default();
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let short = TripInputData::from_times(50.0, begin, begin + 30.minutes());
+        let long = TripInputData { end: begin + 3.days(), ..short };
+        assert_eq!(own_car.calculate(short), own_car.calculate(long));
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    TripInputData,
+    provider::{CalculationResult, ProviderImpl, elapsed},
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Prague public transport (Pražská integrovaná doprava), a baseline that does not care about km.
+/// Unlike a shared car, each of [`TripInputData::passengers`] needs own tickets.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Pid {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Ticket {
+    name: &'static str,
+    minutes: u64,
+    czk: f64,
+}
+
+/// From the longest one, which is how [`Pid::cheapest_tickets()`] tries them.
+const TICKETS: [Ticket; 3] = [
+    Ticket { name: "24 hodin", minutes: 24 * 60, czk: 120.0 },
+    Ticket { name: "90 minut", minutes: 90, czk: 40.0 },
+    Ticket { name: "30 minut", minutes: 30, czk: 30.0 },
+];
+
+impl ProviderImpl for Pid {
+    fn name(&self) -> &'static str {
+        "MHD (PID)"
+    }
+
+    fn id(&self) -> &'static str {
+        "pid"
+    }
+
+    fn calculate(&self, input_data: TripInputData) -> Result<CalculationResult> {
+        Ok(Pid::calculate(self, input_data))
+    }
+}
+
+impl Pid {
+    pub fn calculate(&self, input_data: TripInputData) -> CalculationResult {
+        let minutes = input_data.rounding.minutes(elapsed(input_data.begin, input_data.end));
+        let passengers = f64::from(input_data.passengers);
+
+        let mut result = CalculationResult::new("jízdenky");
+        for (ticket, count) in Self::cheapest_tickets(minutes) {
+            let name = match input_data.passengers {
+                1 => format!("{count}× jízdenka {}", ticket.name),
+                n @ 2..=4 => format!("{count}× jízdenka {} pro {n} osoby", ticket.name),
+                n => format!("{count}× jízdenka {} pro {n} osob", ticket.name),
+            };
+            result.add_component(count as f64 * ticket.czk * passengers, name);
+        }
+        result
+    }
+
+    /// Cheapest counts of [`TICKETS`] (non-zero only) that together last at least `minutes`.
+    /// Even the shortest trip needs a ticket.
+    fn cheapest_tickets(minutes: i64) -> Vec<(Ticket, u64)> {
+        let minutes = minutes.max(1).unsigned_abs();
+        let [long, middle, short] = TICKETS;
+
+        // Shorter tickets lasting a whole day cost more than a long one, so the cheapest
+        // combination leaves less than a day to them: at most two long ticket counts to try.
+        let mut best: Option<(f64, [u64; 3])> = None;
+        for long_count in minutes / long.minutes..=minutes.div_ceil(long.minutes) {
+            let remaining = minutes.saturating_sub(long_count * long.minutes);
+            for middle_count in 0..=remaining.div_ceil(middle.minutes) {
+                let remaining = remaining.saturating_sub(middle_count * middle.minutes);
+                let short_count = remaining.div_ceil(short.minutes);
+                let counts = [long_count, middle_count, short_count];
+                let czk: f64 = TICKETS.iter().zip(counts).map(|(t, n)| n as f64 * t.czk).sum();
+                if best.is_none_or(|(best_czk, _)| czk < best_czk) {
+                    best = Some((czk, counts));
+                }
+            }
+        }
+
+        let (_, counts) = best.expect("at least one combination is tried");
+        TICKETS.into_iter().zip(counts).filter(|&(_, count)| count > 0).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::{ToSpan, civil::date};
+    use test_log::test;
+
+    fn components(passengers: u32, minutes: i64) -> Vec<(String, f64)> {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData {
+            passengers,
+            ..TripInputData::from_times(100.0, begin, begin + minutes.minutes())
+        };
+        Pid::default()
+            .calculate(input_data)
+            .components
+            .into_iter()
+            .map(|c| (c.name, c.czk))
+            .collect()
+    }
+
+    #[test]
+    fn test_cheapest_tickets() {
+        assert_eq!(components(1, 20), [("1× jízdenka 30 minut".to_string(), 30.0)]);
+        assert_eq!(components(1, 70), [("1× jízdenka 90 minut".to_string(), 40.0)]);
+        assert_eq!(components(1, 5 * 60), [("1× jízdenka 24 hodin".to_string(), 120.0)]);
+        assert_eq!(
+            components(1, 24 * 60 + 100),
+            [
+                ("1× jízdenka 24 hodin".to_string(), 120.0),
+                ("1× jízdenka 90 minut".into(), 40.0),
+                ("1× jízdenka 30 minut".into(), 30.0),
+            ]
+        );
+        assert_eq!(components(1, 0), [("1× jízdenka 30 minut".to_string(), 30.0)]);
+    }
+
+    #[test]
+    fn test_long_trip() {
+        // The assumption of `cheapest_tickets()`.
+        let [long, middle, short] = TICKETS;
+        for shorter in [middle, short] {
+            assert!(long.minutes.div_ceil(shorter.minutes) as f64 * shorter.czk > long.czk);
+        }
+
+        let year = 365 * 24 * 60;
+        assert_eq!(components(1, year), [("365× jízdenka 24 hodin".to_string(), 365.0 * 120.0)]);
+        assert_eq!(
+            Pid::cheapest_tickets(i64::MAX),
+            [(long, i64::MAX.unsigned_abs().div_ceil(long.minutes))]
+        );
+    }
+
+    #[test]
+    fn test_passengers() {
+        assert_eq!(components(3, 70), [("1× jízdenka 90 minut pro 3 osoby".to_string(), 120.0)]);
+    }
+}
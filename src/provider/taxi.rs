@@ -0,0 +1,182 @@
+use crate::{
+    FormEvent, TripInputData,
+    provider::{CalculationResult, ProviderImpl, TimeWindow, elapsed},
+};
+use anyhow::Result;
+use dioxus::prelude::*;
+use jiff::civil::Time;
+use serde::{Deserialize, Serialize};
+
+/// Taxi (e.g. Liftago) billed by the distance and the time actually driven, not for waiting.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Taxi {
+    base_czk: f64,
+    per_km_czk: f64,
+    per_minute_czk: f64,
+    /// Added to the fare of trips beginning in [`Taxi::night`].
+    night_surcharge_percent: f64,
+    night: TimeWindow,
+}
+
+impl ProviderImpl for Taxi {
+    fn name(&self) -> &'static str {
+        "taxi"
+    }
+
+    fn id(&self) -> &'static str {
+        "taxi"
+    }
+
+    fn calculate(&self, input_data: TripInputData) -> Result<CalculationResult> {
+        Ok(Taxi::calculate(self, input_data))
+    }
+
+    fn render_input(taxi: Signal<Self>, _input_data: TripInputData) -> Element {
+        rsx! { TaxiInput { taxi } }
+    }
+}
+
+impl Taxi {
+    pub fn calculate(&self, input_data: TripInputData) -> CalculationResult {
+        let rounding = input_data.rounding;
+        let minutes = input_data
+            .driving_minutes
+            .unwrap_or_else(|| rounding.minutes(elapsed(input_data.begin, input_data.end)));
+        let km = rounding.round(input_data.km);
+
+        let mut result = CalculationResult::new(self.name());
+        result.add_component(self.base_czk, "nástupní sazba");
+        result.add_component(km * self.per_km_czk, format!("{km} km"));
+        let driving_czk = minutes as f64 * self.per_minute_czk;
+        result.add_component(driving_czk, format!("jízda {minutes} minut"));
+        if self.night.contains(input_data.begin.time()) && self.night_surcharge_percent > 0.0 {
+            let percent = self.night_surcharge_percent;
+            result.add_component(
+                result.total_czk() * percent / 100.0,
+                format!("noční příplatek {percent} %"),
+            );
+        }
+        result
+    }
+}
+
+impl Default for Taxi {
+    fn default() -> Self {
+        Self {
+            base_czk: 60.0,
+            per_km_czk: 28.0,
+            per_minute_czk: 6.0,
+            night_surcharge_percent: 20.0,
+            night: TimeWindow {
+                start: Time::constant(22, 0, 0, 0),
+                end: Time::constant(6, 0, 0, 0),
+            },
+        }
+    }
+}
+
+#[component]
+pub fn TaxiInput(taxi: Signal<Taxi>) -> Element {
+    let name = taxi.read().name();
+
+    let base_changed = move |evt: FormEvent| {
+        taxi.write().base_czk = evt.parsed()?;
+        Ok(())
+    };
+    let per_km_changed = move |evt: FormEvent| {
+        taxi.write().per_km_czk = evt.parsed()?;
+        Ok(())
+    };
+    let per_minute_changed = move |evt: FormEvent| {
+        taxi.write().per_minute_czk = evt.parsed()?;
+        Ok(())
+    };
+    let night_surcharge_changed = move |evt: FormEvent| {
+        taxi.write().night_surcharge_percent = evt.parsed()?;
+        Ok(())
+    };
+
+    let night = taxi.read().night;
+    rsx! {
+        p {
+            label { for: "provider-{name}-base", "Nástupní sazba (Kč): " },
+            input { id: "provider-{name}-base",
+                r#type: "number",
+                value: taxi.read().base_czk,
+                onchange: base_changed,
+                min: 0,
+            },
+        }
+        p {
+            label { for: "provider-{name}-per-km", "Kč/km: " },
+            input { id: "provider-{name}-per-km",
+                r#type: "number",
+                value: taxi.read().per_km_czk,
+                onchange: per_km_changed,
+                min: 0,
+                step: 0.1,
+            },
+            label { for: "provider-{name}-per-minute", " Kč/min: " },
+            input { id: "provider-{name}-per-minute",
+                r#type: "number",
+                value: taxi.read().per_minute_czk,
+                onchange: per_minute_changed,
+                min: 0,
+                step: 0.1,
+            },
+        }
+        p {
+            label { for: "provider-{name}-night-surcharge",
+                "Noční příplatek {night.start.hour()}-{night.end.hour()}h (%): "
+            },
+            input { id: "provider-{name}-night-surcharge",
+                r#type: "number",
+                value: taxi.read().night_surcharge_percent,
+                onchange: night_surcharge_changed,
+                min: 0,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::{ToSpan, civil::date};
+    use test_log::test;
+
+    #[test]
+    fn test_calculate() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data = TripInputData::from_times(10.0, begin, begin + 2.hours());
+        let taxi = Taxi::default();
+
+        let rental_time = taxi.calculate(input_data);
+        assert_eq!(rental_time.total_czk(), 60.0 + 10.0 * 28.0 + 120.0 * 6.0);
+
+        let driving = TripInputData { driving_minutes: Some(20), ..input_data };
+        let result = taxi.calculate(driving);
+        let components: Vec<_> = result.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(components, ["nástupní sazba", "10 km", "jízda 20 minut"]);
+        assert_eq!(result.total_czk(), 60.0 + 10.0 * 28.0 + 20.0 * 6.0);
+    }
+
+    #[test]
+    fn test_night_surcharge() {
+        let taxi = Taxi::default();
+        let fare = |hour| {
+            let begin = date(2025, 6, 3).at(hour, 30, 0, 0);
+            let input_data = TripInputData {
+                driving_minutes: Some(10),
+                ..TripInputData::from_times(5.0, begin, begin + 10.minutes())
+            };
+            taxi.calculate(input_data).total_czk()
+        };
+        let day_czk = 60.0 + 5.0 * 28.0 + 10.0 * 6.0;
+
+        assert_eq!(fare(21), day_czk);
+        assert_eq!(fare(23), day_czk * 1.2);
+        assert_eq!(fare(2), day_czk * 1.2);
+        assert_eq!(fare(6), day_czk);
+    }
+}
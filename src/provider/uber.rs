@@ -0,0 +1,215 @@
+use crate::{
+    FormEvent, TripInputData,
+    provider::{CalculationResult, ProviderImpl, elapsed},
+};
+use anyhow::Result;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+
+/// Surge multipliers the user can choose from.
+const SURGE: RangeInclusive<f64> = 1.0..=3.0;
+
+/// Uber ride hailing, billed like [`Taxi`](crate::provider::taxi::Taxi) for the distance and the
+/// time driven, both multiplied by [`Uber::surge`] when the demand is high.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Uber {
+    tier: UberTier,
+    surge: f64,
+}
+
+impl ProviderImpl for Uber {
+    fn name(&self) -> &'static str {
+        "Uber"
+    }
+
+    fn id(&self) -> &'static str {
+        "uber"
+    }
+
+    fn calculate(&self, input_data: TripInputData) -> Result<CalculationResult> {
+        Ok(Uber::calculate(self, input_data))
+    }
+
+    fn render_input(uber: Signal<Self>, _input_data: TripInputData) -> Element {
+        rsx! { UberInput { uber } }
+    }
+}
+
+impl Uber {
+    pub fn calculate(&self, input_data: TripInputData) -> CalculationResult {
+        let rates = self.tier.rates();
+        let rounding = input_data.rounding;
+        let minutes = input_data
+            .driving_minutes
+            .unwrap_or_else(|| rounding.minutes(elapsed(input_data.begin, input_data.end)));
+        let km = rounding.round(input_data.km);
+        let surge = self.surge;
+
+        let mut result = CalculationResult::new(self.tier.label());
+        result.add_component(rates.base_czk, "nástupní sazba");
+        result.add_component(km * rates.per_km_czk * surge, format!("{km} km"));
+        result.add_component(
+            minutes as f64 * rates.per_minute_czk * surge,
+            format!("jízda {minutes} minut"),
+        );
+        if surge > 1.0 {
+            result.add_component(0.0, format!("km a minuty za {surge}× vytížení"));
+        }
+        result.top_up_to(rates.min_fare_czk);
+        result
+    }
+}
+
+impl Default for Uber {
+    fn default() -> Self {
+        Self { tier: UberTier::default(), surge: 1.0 }
+    }
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumIter,
+    Display,
+    EnumString,
+    Serialize,
+    Deserialize,
+)]
+pub enum UberTier {
+    #[default]
+    UberX,
+    Comfort,
+    Black,
+}
+
+impl UberTier {
+    fn label(&self) -> &'static str {
+        match self {
+            UberTier::UberX => "UberX",
+            UberTier::Comfort => "Comfort",
+            UberTier::Black => "Black",
+        }
+    }
+
+    /// Prague prices of the tier, without surge.
+    fn rates(&self) -> UberRates {
+        match self {
+            UberTier::UberX => UberRates {
+                base_czk: 40.0,
+                per_km_czk: 14.0,
+                per_minute_czk: 3.5,
+                min_fare_czk: 89.0,
+            },
+            UberTier::Comfort => UberRates {
+                base_czk: 50.0,
+                per_km_czk: 17.0,
+                per_minute_czk: 4.2,
+                min_fare_czk: 119.0,
+            },
+            UberTier::Black => UberRates {
+                base_czk: 80.0,
+                per_km_czk: 25.0,
+                per_minute_czk: 6.0,
+                min_fare_czk: 199.0,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct UberRates {
+    base_czk: f64,
+    per_km_czk: f64,
+    per_minute_czk: f64,
+    min_fare_czk: f64,
+}
+
+#[component]
+pub fn UberInput(uber: Signal<Uber>) -> Element {
+    let name = uber.read().name();
+
+    let tier_changed = move |evt: FormEvent| {
+        uber.write().tier = evt.parsed()?;
+        Ok(())
+    };
+    let surge_changed = move |evt: FormEvent| {
+        let surge: f64 = evt.parsed()?;
+        uber.write().surge = surge.clamp(*SURGE.start(), *SURGE.end());
+        Ok(())
+    };
+
+    let current = *uber.read();
+
+    rsx! {
+        p {
+            label { for: "provider-{name}-tier", "Třída: " },
+            select { id: "provider-{name}-tier",
+                onchange: tier_changed,
+                for tier in UberTier::iter() {
+                    option { value: "{tier}",
+                        selected: current.tier == tier,
+                        "{tier.label()}"
+                    }
+                }
+            }
+        }
+        p {
+            label { for: "provider-{name}-surge", "Vytížení: " },
+            input { id: "provider-{name}-surge",
+                r#type: "range",
+                value: current.surge,
+                oninput: surge_changed,
+                min: *SURGE.start(),
+                max: *SURGE.end(),
+                step: 0.1,
+            },
+            " {current.surge}×"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::{ToSpan, civil::date};
+    use test_log::test;
+
+    fn input_data() -> TripInputData {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        TripInputData {
+            driving_minutes: Some(20),
+            ..TripInputData::from_times(10.0, begin, begin + 2.hours())
+        }
+    }
+
+    #[test]
+    fn test_calculate() {
+        let result = Uber::default().calculate(input_data());
+        let components: Vec<_> =
+            result.components.iter().map(|c| (c.name.as_str(), c.czk)).collect();
+        assert_eq!(
+            components,
+            [("nástupní sazba", 40.0), ("10 km", 10.0 * 14.0), ("jízda 20 minut", 20.0 * 3.5)]
+        );
+
+        let surge = Uber { surge: 2.0, ..Uber::default() }.calculate(input_data());
+        assert_eq!(surge.total_czk(), 40.0 + 2.0 * (10.0 * 14.0 + 20.0 * 3.5), "base not surged");
+        assert_eq!(surge.components.last().unwrap().name, "km a minuty za 2× vytížení");
+    }
+
+    #[test]
+    fn test_min_fare() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let short = TripInputData::from_times(1.0, begin, begin + 3.minutes());
+        assert_eq!(Uber::default().calculate(short).total_czk(), 89.0);
+
+        let black = Uber { tier: UberTier::Black, ..Uber::default() };
+        assert!(black.calculate(input_data()).total_czk() > 199.0);
+    }
+}
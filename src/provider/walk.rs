@@ -0,0 +1,125 @@
+use crate::{
+    FormEvent, TripInputData,
+    provider::{CalculationResult, ProviderImpl, elapsed},
+};
+use anyhow::Result;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Free baseline: for short enough trips the honest answer is to walk or cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Walk {
+    max_km: f64,
+    max_minutes: i64,
+}
+
+impl ProviderImpl for Walk {
+    fn name(&self) -> &'static str {
+        "pěšky/na kole"
+    }
+
+    fn id(&self) -> &'static str {
+        "walk"
+    }
+
+    fn calculate(&self, input_data: TripInputData) -> Result<CalculationResult> {
+        Ok(Walk::calculate(self, input_data))
+    }
+
+    fn unavailable_reason(&self, input_data: TripInputData) -> Option<String> {
+        (!self.is_applicable(input_data)).then(|| "příliš daleko nebo dlouho".to_string())
+    }
+
+    fn render_input(walk: Signal<Self>, _input_data: TripInputData) -> Element {
+        rsx! { WalkInput { walk } }
+    }
+}
+
+impl Walk {
+    /// Whether the trip is short enough (both in distance and time) for the baseline to participate.
+    pub fn is_applicable(&self, input_data: TripInputData) -> bool {
+        let minutes = elapsed(input_data.begin, input_data.end).as_mins();
+        input_data.km <= self.max_km && minutes <= self.max_minutes
+    }
+
+    pub fn calculate(&self, _input_data: TripInputData) -> CalculationResult {
+        let mut result = CalculationResult::new(self.name());
+        result.add_component(0.0, "pěšky/na kole (0 Kč)");
+        result
+    }
+}
+
+impl Default for Walk {
+    fn default() -> Self {
+        Self { max_km: 3.0, max_minutes: 60 }
+    }
+}
+
+#[component]
+pub fn WalkInput(walk: Signal<Walk>) -> Element {
+    let name = walk.read().name();
+
+    let max_km_changed = move |evt: FormEvent| {
+        walk.write().max_km = evt.parsed()?;
+        Ok(())
+    };
+    let max_minutes_changed = move |evt: FormEvent| {
+        walk.write().max_minutes = evt.parsed()?;
+        Ok(())
+    };
+
+    rsx! {
+        p {
+            label { for: "provider-{name}-max-km", "Nejvýše km: " },
+            input { id: "provider-{name}-max-km",
+                r#type: "number",
+                value: walk.read().max_km,
+                onchange: max_km_changed,
+                min: 0,
+            },
+        }
+        p {
+            label { for: "provider-{name}-max-minutes", "Nejvýše minut: " },
+            input { id: "provider-{name}-max-minutes",
+                r#type: "number",
+                value: walk.read().max_minutes,
+                onchange: max_minutes_changed,
+                min: 0,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{Provider, car4way::Car4way, compare_all, tests::with_signals};
+    use jiff::{SignedDuration, civil::date};
+    use test_log::test;
+
+    #[test]
+    fn test_short_trip_walk_wins() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data =
+            TripInputData::from_times(0.5, begin, begin + SignedDuration::from_mins(10));
+
+        with_signals(|| {
+            let car4way = Provider::new(Signal::new(true), Signal::new(Car4way::default()));
+            let walk = Provider::new(Signal::new(true), Signal::new(Walk::default()));
+
+            let results = compare_all(&[car4way, walk], &[input_data]);
+            assert_eq!(results.len(), 2);
+            assert_eq!(results[0].provider_name(), "pěšky/na kole");
+            assert_eq!(results[0].total_czk(), 0.0);
+        })
+    }
+
+    #[test]
+    fn test_long_trip_walk_not_applicable() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let input_data =
+            TripInputData::from_times(12.0, begin, begin + SignedDuration::from_mins(10));
+
+        assert!(!Walk::default().is_applicable(input_data));
+    }
+}
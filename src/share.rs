@@ -0,0 +1,313 @@
+//! Share the trip (and which providers are enabled) as a link with URL query parameters.
+
+use crate::{
+    TripInputData,
+    i18n::{Key, t, use_lang},
+    provider::{Discount, DiscountKind, Provider, wall_clock},
+};
+use anyhow::{Context, Result, bail, ensure};
+use dioxus::prelude::*;
+use std::str::FromStr;
+use tracing::{error, info};
+
+/// What a shared link reproduces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedTrip {
+    pub input_data: TripInputData,
+    /// [Ids](Provider::id) of disabled providers.
+    pub disabled: Vec<String>,
+}
+
+/// Query string (without the leading `?`) of the trip. Distance and times are always present,
+/// other parameters only when they differ from the defaults.
+pub fn encode_query(trip: &SharedTrip) -> String {
+    let input_data = &trip.input_data;
+    let defaults = TripInputData::from_times(input_data.km, input_data.begin, input_data.end);
+
+    let mut params = vec![
+        ("km", input_data.km.to_string()),
+        ("begin", input_data.begin.to_string()),
+        ("end", input_data.end.to_string()),
+    ];
+    let mut push_changed = |name, changed: bool, value: String| {
+        if changed {
+            params.push((name, value));
+        }
+    };
+    if let Some(reservation_begin) = input_data.reservation_begin {
+        push_changed("reservation", true, reservation_begin.to_string());
+    }
+    push_changed(
+        "rounding",
+        input_data.rounding != defaults.rounding,
+        input_data.rounding.to_string(),
+    );
+    push_changed(
+        "return",
+        input_data.return_location != defaults.return_location,
+        input_data.return_location.to_string(),
+    );
+    push_changed(
+        "fuel",
+        input_data.fuel_price_czk_per_l != defaults.fuel_price_czk_per_l,
+        input_data.fuel_price_czk_per_l.to_string(),
+    );
+    push_changed(
+        "idle",
+        input_data.idle_minutes != defaults.idle_minutes,
+        input_data.idle_minutes.to_string(),
+    );
+    if let Some(driving_minutes) = input_data.driving_minutes {
+        push_changed("driving", true, driving_minutes.to_string());
+    }
+    if let Some(budget_czk) = input_data.budget_czk {
+        push_changed("budget", true, budget_czk.to_string());
+    }
+    push_changed("airport_enter", input_data.airport_enter, "1".into());
+    push_changed("airport_leave", input_data.airport_leave, "1".into());
+    push_changed(
+        "passengers",
+        input_data.passengers != defaults.passengers,
+        input_data.passengers.to_string(),
+    );
+    push_changed("round_trip", input_data.round_trip, "1".into());
+    // Like `10%` or `50` (CZK).
+    let discount = input_data.discount;
+    push_changed(
+        "discount",
+        discount.kind != defaults.discount.kind,
+        match discount.kind {
+            DiscountKind::Percent => format!("{}%", discount.amount),
+            DiscountKind::None | DiscountKind::Czk => discount.amount.to_string(),
+        },
+    );
+    push_changed("disabled", !trip.disabled.is_empty(), trip.disabled.join(","));
+
+    form_urlencoded::Serializer::new(String::new()).extend_pairs(params).finish()
+}
+
+/// Parse `query` (with or without the leading `?`) produced by [`encode_query()`], parameters
+/// missing in it are taken from `base`. Unknown parameters are ignored, values out of range are
+/// clamped.
+pub fn decode_query(base: TripInputData, query: &str) -> Result<SharedTrip> {
+    fn parse<T: FromStr>(name: &str, value: &str) -> Result<T>
+    where
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        value.parse().with_context(|| format!("parsing {name}={value}"))
+    }
+    fn parse_flag(name: &str, value: &str) -> Result<bool> {
+        match value {
+            "1" => Ok(true),
+            "0" => Ok(false),
+            _ => bail!("parsing {name}={value}: expected 0 or 1"),
+        }
+    }
+    fn parse_amount(name: &str, value: &str) -> Result<f64> {
+        let amount: f64 = parse(name, value)?;
+        ensure!(amount.is_finite(), "parsing {name}={value}: expected a finite number");
+        Ok(amount.max(0.0))
+    }
+
+    let mut trip = SharedTrip { input_data: base, disabled: vec![] };
+    let input_data = &mut trip.input_data;
+    let query = query.strip_prefix('?').unwrap_or(query);
+    for (name, value) in form_urlencoded::parse(query.as_bytes()) {
+        let (name, value) = (name.as_ref(), value.as_ref());
+        match name {
+            "km" => input_data.km = parse_amount(name, value)?,
+            "begin" => input_data.begin = wall_clock(parse(name, value)?),
+            "end" => input_data.end = wall_clock(parse(name, value)?),
+            "reservation" => input_data.reservation_begin = Some(wall_clock(parse(name, value)?)),
+            "rounding" => input_data.rounding = parse(name, value)?,
+            "return" => input_data.return_location = parse(name, value)?,
+            "fuel" => input_data.fuel_price_czk_per_l = parse_amount(name, value)?,
+            "idle" => input_data.idle_minutes = parse::<i64>(name, value)?.max(0),
+            "driving" => input_data.driving_minutes = Some(parse::<i64>(name, value)?.max(0)),
+            "budget" => input_data.budget_czk = Some(parse_amount(name, value)?),
+            "airport_enter" => input_data.airport_enter = parse_flag(name, value)?,
+            "airport_leave" => input_data.airport_leave = parse_flag(name, value)?,
+            "passengers" => input_data.passengers = parse::<u32>(name, value)?.max(1),
+            "round_trip" => input_data.round_trip = parse_flag(name, value)?,
+            "discount" => {
+                input_data.discount = match value.strip_suffix('%') {
+                    Some(percent) => Discount {
+                        kind: DiscountKind::Percent,
+                        amount: parse_amount(name, percent)?.min(100.0),
+                    },
+                    None => {
+                        Discount { kind: DiscountKind::Czk, amount: parse_amount(name, value)? }
+                    },
+                }
+            },
+            "disabled" => trip.disabled = value.split(',').map(Into::into).collect(),
+            _ => info!("ignoring unknown query parameter {name}"),
+        }
+    }
+    Ok(trip)
+}
+
+/// Once on load, override `input_data` and enabled `providers` by the page URL query, if any.
+pub fn use_shared_trip(input_data: Signal<TripInputData>, providers: &[Provider]) {
+    let providers = providers.to_vec();
+    use_future(move || {
+        let providers = providers.clone();
+        let mut input_data = input_data;
+        async move {
+            let query: String = match document::eval("return window.location.search;").join().await
+            {
+                Ok(query) => query,
+                Err(e) => return error!("reading URL query: {e}"),
+            };
+            if query.is_empty() {
+                return;
+            }
+            let current = *input_data.peek();
+            match decode_query(current, &query) {
+                Ok(trip) => {
+                    input_data.set(trip.input_data);
+                    for provider in &providers {
+                        let mut enabled = provider.enabled;
+                        enabled.set(!trip.disabled.iter().any(|id| id == provider.id()));
+                    }
+                },
+                Err(e) => error!("applying URL query {query}: {e:#}"),
+            }
+        }
+    });
+}
+
+#[component]
+pub fn ShareLink(
+    input_data: Signal<TripInputData>,
+    further_segments: Signal<Vec<TripInputData>>,
+    providers: Vec<Provider>,
+) -> Element {
+    let lang = use_lang();
+    let mut link = use_signal(|| None::<String>);
+
+    let share = move |_| {
+        let disabled = providers
+            .iter()
+            .filter(|provider| !*provider.enabled.read())
+            .map(|provider| provider.id().to_string())
+            .collect();
+        let query = encode_query(&SharedTrip { input_data: *input_data.read(), disabled });
+        let js = format!(
+            "history.replaceState(null, '', '?' + {}); return window.location.href;",
+            serde_json::Value::String(query)
+        );
+        spawn(async move {
+            match document::eval(&js).join().await {
+                Ok(href) => link.set(Some(href)),
+                Err(e) => error!("updating URL: {e}"),
+            }
+        });
+    };
+
+    let segmented = !further_segments.read().is_empty();
+    rsx! {
+        p {
+            // The link carries just the first segment.
+            button { onclick: share, disabled: segmented, "Sdílet odkaz" }
+            if segmented {
+                " ({t(Key::SingleSegmentOnly, lang)})"
+            } else if let Some(link) = link() {
+                " "
+                input { id: "share-link", readonly: true, size: 60, value: link }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{ReturnLocation, Rounding};
+    use jiff::{ToSpan, civil::date};
+    use test_log::test;
+
+    fn base() -> TripInputData {
+        let begin = date(2025, 1, 1).at(8, 0, 0, 0);
+        TripInputData::from_times(12.0, begin, begin + 2.hours())
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let begin = date(2025, 6, 3).at(10, 30, 0, 0);
+        let minimal = SharedTrip {
+            input_data: TripInputData::from_times(42.5, begin, begin + 90.minutes()),
+            disabled: vec![],
+        };
+        let query = encode_query(&minimal);
+        assert_eq!(query, "km=42.5&begin=2025-06-03T10%3A30%3A00&end=2025-06-03T12%3A00%3A00");
+        assert_eq!(decode_query(base(), &format!("?{query}")).unwrap(), minimal);
+
+        let full = SharedTrip {
+            input_data: TripInputData {
+                reservation_begin: Some(begin - 20.minutes()),
+                rounding: Rounding::Optimistic,
+                return_location: ReturnLocation::OutOfZone,
+                fuel_price_czk_per_l: 41.9,
+                idle_minutes: 15,
+                driving_minutes: Some(40),
+                budget_czk: Some(500.0),
+                airport_enter: true,
+                airport_leave: true,
+                passengers: 3,
+                round_trip: true,
+                discount: Discount { kind: DiscountKind::Percent, amount: 10.0 },
+                ..minimal.input_data
+            },
+            disabled: vec!["bolt".into(), "walk".into()],
+        };
+        assert_eq!(decode_query(base(), &encode_query(&full)).unwrap(), full);
+        let czk_discount = Discount { kind: DiscountKind::Czk, amount: 50.0 };
+        let czk = SharedTrip {
+            input_data: TripInputData { discount: czk_discount, ..minimal.input_data },
+            ..minimal.clone()
+        };
+        assert!(encode_query(&czk).ends_with("&discount=50"));
+        assert_eq!(decode_query(base(), &encode_query(&czk)).unwrap(), czk);
+
+        // Values needing escaping survive, e.g. an id with the separators.
+        let odd = SharedTrip { disabled: vec!["a&b=c d".into()], ..minimal };
+        assert_eq!(decode_query(base(), &encode_query(&odd)).unwrap(), odd);
+    }
+
+    #[test]
+    fn test_decode_query() {
+        assert_eq!(decode_query(base(), "").unwrap().input_data, base());
+        let decoded = decode_query(base(), "km=3&utm_source=chat").unwrap();
+        assert_eq!(decoded.input_data, TripInputData { km: 3.0, ..base() });
+        // Hand-written times are Prague wall-clock ones like the entered ones.
+        let decoded = decode_query(base(), "begin=2025-03-30T02:30:00").unwrap();
+        assert_eq!(decoded.input_data.begin, date(2025, 3, 30).at(3, 30, 0, 0));
+        // Decoded like the browser encodes; there's always at least one passenger.
+        let decoded = decode_query(base(), "begin=2025-06-03T10%3A30%3A00&passengers=0").unwrap();
+        assert_eq!(decoded.input_data.begin, date(2025, 6, 3).at(10, 30, 0, 0));
+        assert_eq!(decoded.input_data.passengers, 1);
+        // Negative amounts are clamped too, discounts to at most 100 %.
+        let decoded =
+            decode_query(base(), "km=-5&fuel=-1&idle=-10&budget=-100&discount=150%25").unwrap();
+        assert_eq!(
+            decoded.input_data,
+            TripInputData {
+                km: 0.0,
+                fuel_price_czk_per_l: 0.0,
+                idle_minutes: 0,
+                budget_czk: Some(0.0),
+                discount: Discount { kind: DiscountKind::Percent, amount: 100.0 },
+                ..base()
+            }
+        );
+
+        let error = decode_query(base(), "begin=zítra").unwrap_err();
+        assert_eq!(error.to_string(), "parsing begin=zítra");
+        let error = decode_query(base(), "round_trip=ano").unwrap_err();
+        assert_eq!(error.to_string(), "parsing round_trip=ano: expected 0 or 1");
+        let error = decode_query(base(), "km=NaN").unwrap_err();
+        assert_eq!(error.to_string(), "parsing km=NaN: expected a finite number");
+        assert!(decode_query(base(), "budget=inf").is_err());
+    }
+}
@@ -0,0 +1,244 @@
+//! The whole comparison as JSON, e.g. to attach to a bug report and load it back.
+
+use crate::{
+    TripInputData,
+    export::download,
+    provider::{CalculationResult, Provider},
+};
+use anyhow::{Context, Result, bail};
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::error;
+
+/// Everything the comparison is calculated from, and its results for whoever reads the JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppState {
+    pub input_data: TripInputData,
+    pub further_segments: Vec<TripInputData>,
+    /// By [id](Provider::id).
+    pub providers: BTreeMap<String, ProviderSnapshot>,
+    /// Informational only, they are recalculated after an import.
+    #[serde(default, skip_deserializing)]
+    pub results: Vec<CalculationResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderSnapshot {
+    pub enabled: bool,
+    /// See [`Provider::settings()`].
+    pub settings: serde_json::Value,
+}
+
+impl AppState {
+    /// Snapshot of the current state of `providers`.
+    pub fn new(
+        input_data: TripInputData,
+        further_segments: Vec<TripInputData>,
+        providers: &[Provider],
+        results: Vec<CalculationResult>,
+    ) -> Result<Self> {
+        let providers = providers
+            .iter()
+            .map(|provider| {
+                let snapshot = ProviderSnapshot {
+                    enabled: *provider.enabled.read(),
+                    settings: provider.settings()?,
+                };
+                Ok((provider.id().to_string(), snapshot))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { input_data, further_segments, providers, results })
+    }
+
+    /// Parse JSON produced by [`AppState::to_json()`].
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context("malformed JSON")
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Set `providers` to the stored state, those missing in it keep theirs. Nothing is changed
+    /// if some stored provider is unknown or has invalid settings.
+    pub fn apply_to(self, providers: &[Provider]) -> Result<(TripInputData, Vec<TripInputData>)> {
+        if let Some(unknown) =
+            self.providers.keys().find(|id| !providers.iter().any(|p| p.id() == *id))
+        {
+            bail!("unknown provider {unknown}");
+        }
+        let mut changes = Vec::new();
+        for provider in providers {
+            let Some(snapshot) = self.providers.get(provider.id()) else {
+                continue;
+            };
+            let set_settings = provider.prepare_settings(snapshot.settings.clone())?;
+            changes.push((set_settings, provider.enabled, snapshot.enabled));
+        }
+        for (set_settings, mut enabled, stored_enabled) in changes {
+            set_settings();
+            enabled.set(stored_enabled);
+        }
+        Ok((self.input_data, self.further_segments))
+    }
+}
+
+#[component]
+pub fn StateJson(
+    input_data: Signal<TripInputData>,
+    further_segments: Signal<Vec<TripInputData>>,
+    providers: Vec<Provider>,
+    results: Memo<Vec<CalculationResult>>,
+) -> Element {
+    let mut json = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+
+    let export = {
+        let providers = providers.clone();
+        move |_| {
+            let state = AppState::new(input_data(), further_segments(), &providers, results());
+            match state.and_then(|state| state.to_json()) {
+                Ok(json) => download("za-kolik-pojedu.json", "application/json", &json),
+                Err(e) => error!("exporting JSON: {e:#}"),
+            }
+        }
+    };
+    let import = move |_| match AppState::from_json(&json.read())
+        .and_then(|state| state.apply_to(&providers))
+    {
+        Ok((imported, imported_segments)) => {
+            input_data.set(imported);
+            further_segments.set(imported_segments);
+            error.set(None);
+        },
+        Err(e) => error.set(Some(format!("{e:#}"))),
+    };
+
+    rsx! {
+        details {
+            summary { "Stav srovnání (JSON)" },
+            p {
+                button { onclick: export, "Exportovat JSON" }
+            }
+            p {
+                textarea { id: "state-json",
+                    placeholder: "{{\"input_data\": …}}",
+                    rows: 10,
+                    cols: 80,
+                    value: "{json}",
+                    oninput: move |evt| json.set(evt.value()),
+                }
+            }
+            p {
+                button { onclick: import, "Importovat JSON" }
+            }
+            if let Some(error) = error() {
+                p { class: "unavailable", "Import selhal: {error}" }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{bolt::Bolt, tests::with_signals, walk::Walk};
+    use jiff::{ToSpan, civil::date};
+    use test_log::test;
+
+    #[test]
+    fn test_json_roundtrip() {
+        with_signals(|| {
+            let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+            let input_data = TripInputData {
+                passengers: 2,
+                airport_enter: true,
+                ..TripInputData::from_times(12.5, begin, begin + 90.minutes())
+            };
+            let further =
+                vec![TripInputData::from_times(3.0, begin + 3.hours(), begin + 4.hours())];
+            let bolt = Provider::new(Signal::new(true), Signal::new(Bolt::default()));
+            let walk = Provider::new(Signal::new(false), Signal::new(Walk::default()));
+            let providers = [bolt.clone(), walk.clone()];
+            let results = vec![bolt.calculate(input_data).unwrap()];
+
+            let state = AppState::new(input_data, further.clone(), &providers, results).unwrap();
+            let json = state.to_json().unwrap();
+            let parsed = AppState::from_json(&json).unwrap();
+            assert_eq!(parsed, AppState { results: vec![], ..state.clone() });
+            assert!(json.contains("\"provider\": \"Bolt\""), "results are exported: {json}");
+
+            // Changes made after the export are reverted by the import.
+            let mut bolt_enabled = bolt.enabled;
+            bolt_enabled.set(false);
+            bolt.set_settings(serde_json::json!({
+                "category": "Premium",
+                "driving_per_minute_czk": 1.0,
+                "per_km_czk": null,
+                "min_charge_czk": null,
+            }))
+            .unwrap();
+            let (imported, imported_further) = parsed.apply_to(&providers).unwrap();
+            assert_eq!((imported, imported_further), (input_data, further));
+            let reimported = AppState::new(imported, vec![], &providers, vec![]).unwrap();
+            assert_eq!(reimported.providers, state.providers);
+        })
+    }
+
+    #[test]
+    fn test_malformed_json() {
+        with_signals(|| {
+            let providers = [Provider::new(Signal::new(true), Signal::new(Walk::default()))];
+            let error = |json: &str| {
+                format!(
+                    "{:#}",
+                    AppState::from_json(json).and_then(|s| s.apply_to(&providers)).unwrap_err()
+                )
+            };
+
+            assert!(error("{").starts_with("malformed JSON: EOF while parsing"), "{}", error("{"));
+            let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+            let state = AppState::new(
+                TripInputData::from_times(1.0, begin, begin),
+                vec![],
+                &providers,
+                vec![],
+            )
+            .unwrap();
+            let json = state.to_json().unwrap();
+            assert_eq!(error(&json.replace("\"walk\"", "\"tram\"")), "unknown provider tram");
+            let bad_settings = json.replace("\"max_km\"", "\"km\"");
+            assert!(
+                error(&bad_settings).starts_with("settings of walk: missing field `max_km`"),
+                "{}",
+                error(&bad_settings)
+            );
+        })
+    }
+
+    #[test]
+    fn test_invalid_settings_change_nothing() {
+        with_signals(|| {
+            let bolt = Provider::new(Signal::new(true), Signal::new(Bolt::default()));
+            let walk = Provider::new(Signal::new(true), Signal::new(Walk::default()));
+            let providers = [bolt.clone(), walk.clone()];
+            let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+            let input_data = TripInputData::from_times(1.0, begin, begin);
+            let state = AppState::new(input_data, vec![], &providers, vec![]).unwrap();
+
+            let mut stored = state.clone();
+            let bolt_snapshot = stored.providers.get_mut("bolt").unwrap();
+            bolt_snapshot.enabled = false;
+            bolt_snapshot.settings["category"] = "Premium".into();
+            stored.providers.get_mut("walk").unwrap().settings = serde_json::json!({});
+
+            let error = stored.apply_to(&providers).unwrap_err();
+            assert!(format!("{error:#}").starts_with("settings of walk: "), "{error:#}");
+            // Bolt, coming first, is left untouched too.
+            assert!(*bolt.enabled.read());
+            let unchanged = AppState::new(input_data, vec![], &providers, vec![]).unwrap();
+            assert_eq!(unchanged.providers, state.providers);
+        })
+    }
+}
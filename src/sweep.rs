@@ -0,0 +1,443 @@
+//! What-if analysis: hold some trip inputs, vary another one and watch the prices.
+
+use crate::{
+    FormEvent, TripInputData,
+    currency::use_currency,
+    i18n::{Key, t, use_lang},
+    provider::{Provider, after},
+};
+use anyhow::{Context, Result, ensure};
+use dioxus::prelude::*;
+use jiff::{SignedDuration, civil::DateTime};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+
+/// The trip input varied by a [`Sweep`], the other ones are held.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, EnumIter, Display, EnumString)]
+pub enum SweepVariable {
+    Km,
+    Begin,
+    #[default]
+    End,
+}
+
+impl SweepVariable {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SweepVariable::Km => "počet km",
+            SweepVariable::Begin => "začátek",
+            SweepVariable::End => "konec",
+        }
+    }
+
+    fn step_unit(&self) -> &'static str {
+        match self {
+            SweepVariable::Km => "km",
+            SweepVariable::Begin | SweepVariable::End => "min",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sweep {
+    pub variable: SweepVariable,
+    /// When varying begin or end, move the other one along to keep the trip duration.
+    pub lock_duration: bool,
+    /// Increment in kilometers or minutes, depending on [`Sweep::variable`].
+    pub step: f64,
+    pub steps: u32,
+}
+
+impl Sweep {
+    /// `base` followed by [`Sweep::steps`] inputs, each varied by one more [`Sweep::step`]. Fails
+    /// if some of them would be out of range.
+    pub fn inputs(&self, base: TripInputData) -> Result<Vec<TripInputData>> {
+        (0..=self.steps).map(|i| self.input(base, f64::from(i) * self.step)).collect()
+    }
+
+    fn input(&self, base: TripInputData, amount: f64) -> Result<TripInputData> {
+        let shifted = |datetime: DateTime| -> Result<DateTime> {
+            let shift = SignedDuration::try_from_secs_f64(amount * 60.0)
+                .with_context(|| format!("shifting by {amount} min"))?;
+            datetime.checked_add(shift).with_context(|| format!("shifting {datetime} by {shift}"))
+        };
+        Ok(match (self.variable, self.lock_duration) {
+            (SweepVariable::Km, _) => {
+                let km = base.km + amount;
+                ensure!(km.is_finite(), "{km} km");
+                TripInputData { km, ..base }
+            },
+            (SweepVariable::Begin | SweepVariable::End, true) => {
+                TripInputData { begin: shifted(base.begin)?, end: shifted(base.end)?, ..base }
+            },
+            (SweepVariable::Begin, false) => TripInputData { begin: shifted(base.begin)?, ..base },
+            (SweepVariable::End, false) => TripInputData { end: shifted(base.end)?, ..base },
+        })
+    }
+
+    /// What distinguishes `input_data` within the sweep.
+    fn point_label(&self, input_data: &TripInputData) -> String {
+        match self.variable {
+            SweepVariable::Km => format!("{} km", input_data.km),
+            SweepVariable::Begin => input_data.begin.strftime("%-d. %-m. %H:%M").to_string(),
+            SweepVariable::End => input_data.end.strftime("%-d. %-m. %H:%M").to_string(),
+        }
+    }
+}
+
+impl Default for Sweep {
+    fn default() -> Self {
+        Self { variable: SweepVariable::default(), lock_duration: false, step: 30.0, steps: 8 }
+    }
+}
+
+#[component]
+pub fn SweepView(
+    providers: Vec<Provider>,
+    input_data: Signal<TripInputData>,
+    further_segments: Signal<Vec<TripInputData>>,
+) -> Element {
+    const MAX_STEPS: u32 = 50;
+
+    let lang = use_lang();
+    let currency = use_currency();
+    let mut sweep = use_signal(Sweep::default);
+    if !further_segments.read().is_empty() {
+        return rsx! {
+            details { id: "sweep", class: "top-section",
+                summary { "Co kdyby: měnit jeden údaj" },
+                p { class: "unavailable", {t(Key::SingleSegmentOnly, lang)} }
+            }
+        };
+    }
+
+    let variable_changed = move |evt: FormEvent| {
+        sweep.write().variable = evt.parsed()?;
+        Ok(())
+    };
+    let lock_duration_changed = move |evt: FormEvent| {
+        sweep.write().lock_duration = evt.checked();
+    };
+    let step_changed = move |evt: FormEvent| {
+        sweep.write().step = evt.parsed()?;
+        Ok(())
+    };
+    let steps_changed = move |evt: FormEvent| {
+        sweep.write().steps = evt.parsed::<u32>()?.min(MAX_STEPS);
+        Ok(())
+    };
+
+    let current = *sweep.read();
+    let enabled: Vec<_> = providers.into_iter().filter(|p| *p.enabled.read()).collect();
+    let inputs = current.inputs(*input_data.read());
+    let rows: Vec<_> = inputs
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .map(|&input_data| {
+            let prices: Vec<_> = enabled
+                .iter()
+                .map(|provider| {
+                    if !provider.is_applicable(input_data) {
+                        return "—".to_string();
+                    }
+                    match provider.calculate(input_data) {
+                        Ok(result) => currency.short(result.total_czk()),
+                        Err(_) => "chyba".to_string(),
+                    }
+                })
+                .collect();
+            (current.point_label(&input_data), prices)
+        })
+        .collect();
+    let held = match (current.variable, current.lock_duration) {
+        (SweepVariable::Km, _) => "začátek a konec",
+        (SweepVariable::Begin | SweepVariable::End, true) => "počet km a délka cesty",
+        (SweepVariable::Begin, false) => "počet km a konec",
+        (SweepVariable::End, false) => "počet km a začátek",
+    };
+
+    rsx! {
+        details { id: "sweep", class: "top-section",
+            summary { "Co kdyby: měnit jeden údaj" },
+            p {
+                label { for: "sweep-variable", "Měnit " },
+                select { id: "sweep-variable",
+                    onchange: variable_changed,
+                    for variable in SweepVariable::iter() {
+                        option { value: "{variable}",
+                            selected: current.variable == variable,
+                            "{variable.label()}"
+                        }
+                    }
+                }
+                " po "
+                input { id: "sweep-step",
+                    r#type: "number",
+                    value: current.step,
+                    onchange: step_changed,
+                },
+                " {current.variable.step_unit()}, "
+                input { id: "sweep-steps",
+                    r#type: "number",
+                    value: current.steps,
+                    onchange: steps_changed,
+                    min: 1,
+                    max: MAX_STEPS,
+                },
+                " krát"
+            }
+            p {
+                input { id: "sweep-lock-duration",
+                    r#type: "checkbox",
+                    checked: current.lock_duration,
+                    disabled: current.variable == SweepVariable::Km,
+                    onchange: lock_duration_changed,
+                }
+                label { for: "sweep-lock-duration", " zachovat délku cesty" },
+                span { class: "note", " (drží se: {held})" }
+            }
+            table { class: "sweep",
+                tr {
+                    th { "{current.variable.label()}" }
+                    for provider in enabled.iter() {
+                        th { "{provider.name()}" }
+                    }
+                }
+                for (label, prices) in rows {
+                    tr {
+                        td { "{label}" }
+                        for price in prices {
+                            td { "{price}" }
+                        }
+                    }
+                }
+            }
+            if let Err(e) = inputs {
+                p { class: "unavailable", "chyba: {e:#}" }
+            }
+        }
+    }
+}
+
+/// Trip durations of the [`CostCurve`], by quarters of an hour while packages differ most and
+/// hourly up to a day.
+fn curve_durations() -> Vec<SignedDuration> {
+    let quarters = (1..12).map(|quarter| SignedDuration::from_mins(15 * quarter));
+    let hours = (3..=24).map(SignedDuration::from_hours);
+    quarters.chain(hours).collect()
+}
+
+/// Totals of `provider` for `base` ending after each of [`curve_durations()`], `None` where it is
+/// not applicable or fails to calculate.
+fn cost_curve(provider: &Provider, base: TripInputData) -> Vec<(SignedDuration, Option<f64>)> {
+    curve_durations()
+        .into_iter()
+        .map(|duration| {
+            let input_data = TripInputData { end: after(base.begin, duration), ..base };
+            let result = provider.is_applicable(input_data).then(|| provider.calculate(input_data));
+            (duration, result.and_then(Result::ok).map(|result| result.total_czk()))
+        })
+        .collect()
+}
+
+/// SVG polyline points of `curve` scaled to `width` × `height`, zero at the bottom and the most
+/// expensive point at the top. Durations without a price are left out.
+fn polyline_points(curve: &[(SignedDuration, Option<f64>)], width: f64, height: f64) -> String {
+    let max_secs = curve.iter().map(|(duration, _)| duration.as_secs_f64()).fold(0.0, f64::max);
+    let max_czk = curve.iter().filter_map(|(_, czk)| *czk).fold(0.0, f64::max);
+    curve
+        .iter()
+        .filter_map(|(duration, czk)| {
+            let czk = (*czk)?;
+            let x = if max_secs > 0.0 { duration.as_secs_f64() / max_secs * width } else { 0.0 };
+            let y = if max_czk > 0.0 { height - czk / max_czk * height } else { height };
+            Some(format!("{x:.1},{y:.1}"))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How the price of the chosen provider grows with the trip duration, showing where packages
+/// start to pay off.
+#[component]
+pub fn CostCurve(
+    providers: Vec<Provider>,
+    input_data: Signal<TripInputData>,
+    further_segments: Signal<Vec<TripInputData>>,
+) -> Element {
+    const WIDTH: f64 = 480.0;
+    const HEIGHT: f64 = 200.0;
+
+    let lang = use_lang();
+    let currency = use_currency();
+    let mut selected_id = use_signal(|| None::<String>);
+    let enabled: Vec<_> = providers.iter().filter(|p| *p.enabled.read()).cloned().collect();
+    let selected = |enabled: &[Provider], selected_id: Option<&str>| {
+        let chosen = enabled.iter().find(|provider| Some(provider.id()) == selected_id);
+        chosen.or(enabled.first()).cloned()
+    };
+    // Recalculated only when the trip or the provider (or its settings) change.
+    let curve = use_memo(move || {
+        if !further_segments.read().is_empty() {
+            return None;
+        }
+        let enabled: Vec<_> = providers.iter().filter(|p| *p.enabled.read()).cloned().collect();
+        let provider = selected(&enabled, selected_id.read().as_deref())?;
+        Some((provider.name(), cost_curve(&provider, *input_data.read())))
+    });
+
+    if !further_segments.read().is_empty() {
+        return rsx! {
+            details { id: "cost-curve", class: "top-section",
+                summary { "Co kdyby: cena podle délky cesty" },
+                p { class: "unavailable", {t(Key::SingleSegmentOnly, lang)} }
+            }
+        };
+    }
+
+    let current_id = selected(&enabled, selected_id.read().as_deref()).map(|p| p.id());
+    let curve = curve.read();
+    let points = curve.as_ref().map(|(_, curve)| polyline_points(curve, WIDTH, HEIGHT));
+    let max_czk = curve.iter().flat_map(|(_, curve)| curve).filter_map(|(_, czk)| *czk);
+    let max_czk = max_czk.fold(0.0, f64::max);
+
+    rsx! {
+        details { id: "cost-curve", class: "top-section",
+            summary { "Co kdyby: cena podle délky cesty" },
+            p {
+                label { for: "cost-curve-provider", "Poskytovatel: " },
+                select { id: "cost-curve-provider",
+                    onchange: move |evt: FormEvent| selected_id.set(Some(evt.value())),
+                    for provider in enabled.iter() {
+                        option { value: "{provider.id()}",
+                            selected: current_id == Some(provider.id()),
+                            "{provider.name()}"
+                        }
+                    }
+                }
+            }
+            if let (Some((name, _)), Some(points)) = (curve.as_ref(), points) {
+                svg { class: "cost-curve",
+                    width: WIDTH,
+                    height: HEIGHT + 20.0,
+                    view_box: "0 -20 {WIDTH} {HEIGHT + 20.0}",
+                    role: "img",
+                    title { "{name}: cena za 15 minut až 24 hodin" }
+                    line { class: "axis", x1: 0, y1: HEIGHT, x2: WIDTH, y2: HEIGHT }
+                    polyline { points: "{points}",
+                        fill: "none",
+                        stroke: "currentColor",
+                        stroke_width: 2,
+                    }
+                    text { x: 0, y: -6, "{currency.short(max_czk)}" }
+                    text { x: WIDTH, y: -6, text_anchor: "end", "24 h" }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{taxi::Taxi, tests::with_signals, walk::Walk};
+    use jiff::{ToSpan, civil::date};
+    use test_log::test;
+
+    #[test]
+    fn test_sweep_inputs() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let base = TripInputData::from_times(10.0, begin, begin + 1.hour());
+        let times = |sweep: Sweep| -> Vec<_> {
+            let inputs = sweep.inputs(base).unwrap();
+            inputs.into_iter().map(|input| (input.km, input.begin, input.end)).collect()
+        };
+
+        let end_sweep =
+            Sweep { variable: SweepVariable::End, lock_duration: false, step: 30.0, steps: 2 };
+        assert_eq!(
+            times(end_sweep),
+            [
+                (10.0, begin, begin + 60.minutes()),
+                (10.0, begin, begin + 90.minutes()),
+                (10.0, begin, begin + 120.minutes()),
+            ]
+        );
+
+        let km_sweep = Sweep { variable: SweepVariable::Km, step: 5.0, ..end_sweep };
+        assert_eq!(
+            times(km_sweep),
+            [
+                (10.0, begin, begin + 1.hour()),
+                (15.0, begin, begin + 1.hour()),
+                (20.0, begin, begin + 1.hour()),
+            ]
+        );
+
+        let begin_sweep =
+            Sweep { variable: SweepVariable::Begin, lock_duration: true, step: 15.0, steps: 1 };
+        assert_eq!(
+            times(begin_sweep),
+            [(10.0, begin, begin + 1.hour()), (10.0, begin + 15.minutes(), begin + 75.minutes())]
+        );
+    }
+
+    #[test]
+    fn test_sweep_inputs_out_of_range() {
+        let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+        let base = TripInputData::from_times(10.0, begin, begin + 1.hour());
+        let end_sweep =
+            Sweep { variable: SweepVariable::End, lock_duration: false, step: 30.0, steps: 2 };
+
+        for step in [f64::NAN, f64::INFINITY, 1e300] {
+            let error = Sweep { step, ..end_sweep }.inputs(base).unwrap_err();
+            assert!(error.to_string().starts_with("shifting by "), "{error}");
+        }
+        let km_sweep = Sweep { variable: SweepVariable::Km, step: f64::NAN, ..end_sweep };
+        assert_eq!(km_sweep.inputs(base).unwrap_err().to_string(), "NaN km");
+        // Representable, but beyond the last supported date.
+        let error = Sweep { step: 1e10, ..end_sweep }.inputs(base).unwrap_err();
+        assert!(error.to_string().starts_with("shifting 2025-06-03T11:00:00 by "), "{error}");
+    }
+
+    #[test]
+    fn test_curve_durations() {
+        let durations = curve_durations();
+        assert_eq!(durations.first(), Some(&SignedDuration::from_mins(15)));
+        assert_eq!(durations.last(), Some(&SignedDuration::from_hours(24)));
+        assert!(durations.is_sorted_by(|a, b| a < b));
+        assert_eq!(durations.len(), 11 + 22);
+    }
+
+    #[test]
+    fn test_cost_curve() {
+        with_signals(|| {
+            let begin = date(2025, 6, 3).at(10, 0, 0, 0);
+            let base = TripInputData::from_times(3.0, begin, begin + 1.hour());
+
+            let taxi = Provider::new(Signal::new(true), Signal::new(Taxi::default()));
+            let curve = cost_curve(&taxi, base);
+            assert_eq!(curve.len(), curve_durations().len());
+            let prices: Vec<_> = curve.iter().map(|(_, czk)| czk.unwrap()).collect();
+            assert!(prices.is_sorted_by(|a, b| a < b), "taxi bills every minute: {prices:?}");
+
+            let far = TripInputData { km: 50.0, ..base };
+            let walk = Provider::new(Signal::new(true), Signal::new(Walk::default()));
+            assert!(cost_curve(&walk, far).iter().all(|(_, czk)| czk.is_none()));
+        })
+    }
+
+    #[test]
+    fn test_polyline_points() {
+        let curve = [
+            (SignedDuration::from_mins(30), Some(50.0)),
+            (SignedDuration::from_hours(1), None),
+            (SignedDuration::from_hours(2), Some(100.0)),
+        ];
+        assert_eq!(polyline_points(&curve, 100.0, 50.0), "25.0,25.0 100.0,0.0");
+
+        let free = [(SignedDuration::from_hours(1), Some(0.0))];
+        assert_eq!(polyline_points(&free, 100.0, 50.0), "100.0,50.0");
+        assert_eq!(polyline_points(&[], 100.0, 50.0), "");
+    }
+}
@@ -0,0 +1,89 @@
+//! Light and dark color scheme, remembered by the browser.
+
+use dioxus::prelude::*;
+use serde_json::json;
+use strum::{Display, EnumString};
+use tracing::{error, warn};
+
+/// Local storage key of the chosen [`Theme`].
+const STORAGE_KEY: &str = "theme";
+
+/// Value of the `data-theme` attribute of the document root, the stylesheet picks colors by it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl Theme {
+    fn toggled(self) -> Self {
+        match self {
+            Theme::Light => Theme::Dark,
+            Theme::Dark => Theme::Light,
+        }
+    }
+
+    /// Label of the button switching to the other theme.
+    fn switch_label(self) -> &'static str {
+        match self {
+            Theme::Light => "🌙 tmavý režim",
+            Theme::Dark => "☀️ světlý režim",
+        }
+    }
+}
+
+#[component]
+pub fn ThemeToggle() -> Element {
+    let mut theme = use_signal(Theme::default);
+
+    // Once on load, restore the choice of a previous visit.
+    use_future(move || async move {
+        let js = format!("return localStorage.getItem({});", json!(STORAGE_KEY));
+        let stored: Option<String> = match document::eval(&js).join().await {
+            Ok(stored) => stored,
+            Err(e) => return error!("reading stored theme: {e}"),
+        };
+        match stored.map(|stored| stored.parse()) {
+            Some(Ok(stored)) => theme.set(stored),
+            Some(Err(e)) => warn!("ignoring stored theme: {e}"),
+            None => {},
+        }
+    });
+    use_effect(move || {
+        let js =
+            format!("document.documentElement.dataset.theme = {};", json!(theme().to_string()));
+        document::eval(&js);
+    });
+
+    // Only an explicit choice is stored, not the default.
+    let toggle = move |_| {
+        let toggled = theme().toggled();
+        theme.set(toggled);
+        let js = format!(
+            "localStorage.setItem({}, {});",
+            json!(STORAGE_KEY),
+            json!(toggled.to_string())
+        );
+        document::eval(&js);
+    };
+
+    rsx! {
+        button { id: "theme-toggle", onclick: toggle, "{theme().switch_label()}" }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn test_theme_roundtrip() {
+        assert_eq!(Theme::Dark.to_string(), "dark");
+        assert_eq!("light".parse::<Theme>().unwrap(), Theme::Light);
+        assert!("sepia".parse::<Theme>().is_err());
+        assert_eq!(Theme::default().toggled().toggled(), Theme::default());
+    }
+}